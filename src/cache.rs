@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Default time a cached response stays fresh.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Extension of the entries recording that a URL wasn't found.
+const MISSING: &str = "missing";
+
+/// How the on-disk response cache is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serve fresh cached responses and cache new ones.
+    #[default]
+    Enabled,
+    /// Ignore cached responses, but cache new ones.
+    Refresh,
+    /// Neither read nor write the cache.
+    Disabled,
+    /// Serve every response from the cache, however old, and never touch the
+    /// network.
+    Offline,
+}
+
+/// Response bodies stored on disk, keyed by request URL, along with the URLs
+/// that weren't found.
+///
+/// The cache is best-effort: entries that can't be read or written are
+/// treated as missing.
+pub(crate) struct Cache {
+    dir: Option<PathBuf>,
+    mode: CacheMode,
+    ttl: Duration,
+}
+
+impl Cache {
+    pub fn new(dir: Option<PathBuf>, mode: CacheMode, ttl: Duration) -> Cache {
+        let dir = dir.or_else(|| {
+            directories::ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+                .map(|dirs| dirs.cache_dir().to_path_buf())
+        });
+
+        Cache { dir, mode, ttl }
+    }
+
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Returns the cached body for `url` if it is still fresh, or regardless of
+    /// its age when offline.
+    pub fn get(&self, url: &str) -> Option<String> {
+        let path = self.path(url)?;
+        if !self.usable(&path) {
+            return None;
+        }
+        fs::read_to_string(path).ok()
+    }
+
+    /// Whether `url` was cached as not found, under the same rules as
+    /// [`Cache::get`].
+    pub fn is_missing(&self, url: &str) -> bool {
+        self.path(url)
+            .is_some_and(|path| self.usable(&path.with_extension(MISSING)))
+    }
+
+    pub fn put(&self, url: &str, body: &str) {
+        if let Some(path) = self.path(url) {
+            self.write(&path, body);
+            let _ = fs::remove_file(path.with_extension(MISSING));
+        }
+    }
+
+    /// Caches that `url` wasn't found, so that it isn't found offline either.
+    pub fn put_missing(&self, url: &str) {
+        if let Some(path) = self.path(url) {
+            self.write(&path.with_extension(MISSING), "");
+        }
+    }
+
+    /// Whether the entry at `path` can be served: it exists, and is fresh
+    /// unless offline.
+    fn usable(&self, path: &Path) -> bool {
+        match self.mode {
+            CacheMode::Enabled => {}
+            CacheMode::Offline => return path.exists(),
+            CacheMode::Refresh | CacheMode::Disabled => return false,
+        }
+
+        let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        age <= self.ttl
+    }
+
+    fn write(&self, path: &Path, contents: &str) {
+        if matches!(self.mode, CacheMode::Disabled | CacheMode::Offline) {
+            return;
+        }
+
+        let _ = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, contents));
+    }
+
+    fn path(&self, url: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{:016x}", fnv1a(url.as_bytes()))))
+    }
+}
+
+/// A stable hash for cache file names; `DefaultHasher` may change between
+/// Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
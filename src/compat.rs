@@ -0,0 +1,45 @@
+use crate::{qfapi, MappingsSource, Versions};
+
+/// Lists the resolved versions that are built for a different Minecraft
+/// version than the selected one, going by the Minecraft version embedded in
+/// their version numbers.
+///
+/// Components that don't embed one, like the loader and loom, are not checked.
+pub fn incompatibilities(versions: &Versions) -> Vec<String> {
+    let Versions {
+        platform,
+        minecraft,
+        ..
+    } = versions;
+
+    let mut built_for = Vec::new();
+    if versions.mappings_source == MappingsSource::Platform {
+        // Versioned like `1.20.1+build.23`
+        let embedded = versions.mappings.split_once('+').map(|(mc, _)| mc);
+        built_for.push((
+            platform.mappings_name(),
+            versions.mappings.as_str(),
+            embedded,
+        ));
+    }
+    if let Some(version) = versions.qfapi.found() {
+        let api_platform = versions.api_platform();
+        let embedded = qfapi::minecraft_version(api_platform, version);
+        built_for.push((api_platform.api_name(), version, embedded));
+    }
+    // Versioned like `6.1.2+1.20.1`
+    if let Some(version) = versions.qsl.found() {
+        let embedded = version.split_once('+').map(|(_, mc)| mc);
+        built_for.push(("Quilt Standard Libraries", version, embedded));
+    }
+
+    built_for
+        .into_iter()
+        .filter_map(|(name, version, embedded)| {
+            let embedded = embedded?;
+            (embedded != minecraft).then(|| {
+                format!("{name} {version} is built for Minecraft {embedded}, not {minecraft}")
+            })
+        })
+        .collect()
+}
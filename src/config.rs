@@ -0,0 +1,206 @@
+//! Defaults for the command-line options, read from `quilt-latest.toml`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, CommandFactory};
+use serde::Deserialize;
+
+use crate::{Channel, Cli, Color, Emitted, Format, Loader, Mappings, Repo};
+
+const FILE_NAME: &str = "quilt-latest.toml";
+
+/// Every option that can be given a default. Keys are named like the long
+/// flags, with underscores.
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    loader: Option<Loader>,
+    minecraft: Option<String>,
+    channel: Option<Channel>,
+    allow_prerelease: Option<bool>,
+    mappings: Option<Mappings>,
+    include_unstable_mappings: Option<bool>,
+    snapshot: Option<bool>,
+    qfapi_fallback: Option<bool>,
+    fabric_api_fallback: Option<bool>,
+    fail_if_qfapi_missing: Option<bool>,
+    no_qfapi: Option<bool>,
+    no_qsl: Option<bool>,
+    components: Option<Vec<Emitted>>,
+    kotlin: Option<bool>,
+    hashed: Option<bool>,
+    best_effort: Option<bool>,
+    loader_version: Option<String>,
+    loom_version: Option<String>,
+    loom_major: Option<String>,
+    loom_snapshot: Option<bool>,
+    loom_range: Option<bool>,
+    loom_plugin_id: Option<String>,
+    minecraft_module: Option<String>,
+    maven_repo: Option<Repo>,
+    mappings_version: Option<String>,
+    mappings_build: Option<u64>,
+    qfapi_version: Option<String>,
+    strict: Option<bool>,
+    verify: Option<bool>,
+    quiet: Option<bool>,
+    color: Option<Color>,
+    self_check: Option<bool>,
+    format: Option<Format>,
+    json_pretty: Option<bool>,
+    timeout: Option<u64>,
+    retries: Option<u32>,
+    max_redirects: Option<u32>,
+    jobs: Option<usize>,
+    user_agent: Option<String>,
+    user_agent_suffix: Option<String>,
+    proxy: Option<String>,
+    respect_gradle_props: Option<bool>,
+    meta_url: Option<String>,
+    /// Comma-separated, like `--maven-url`.
+    maven_url: Option<String>,
+    snapshot_url: Option<String>,
+    parchment_url: Option<String>,
+    fabric_maven_url: Option<String>,
+    no_cache: Option<bool>,
+    no_delta: Option<bool>,
+    cache_ttl: Option<u64>,
+    /// Catalog aliases, keyed by the default alias they replace, like
+    /// `--alias`.
+    #[serde(alias = "aliases")]
+    alias: Option<BTreeMap<String, String>>,
+}
+
+impl Config {
+    /// Reads `path`, or else the first `quilt-latest.toml` in the current
+    /// directory or the user's config directory. Without one, nothing is
+    /// overridden.
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let Some(path) = path.map(Path::to_path_buf).or_else(find) else {
+            return Ok(Config::default());
+        };
+
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        toml_edit::de::from_str(&text).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    /// Fills in the options of `cli` that were not given on the command line
+    /// or through an environment variable. An option that conflicts with one
+    /// given there is left alone too, so the command line always wins.
+    pub fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let mut command = Cli::command();
+        command.build();
+        let given = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        };
+        let clashes = |arg: &Arg, id: &str| {
+            command
+                .get_arg_conflicts_with(arg)
+                .iter()
+                .any(|other| other.get_id() == id)
+        };
+        let explicit = |id: &str| {
+            let this = command.get_arguments().find(|arg| arg.get_id() == id);
+            given(id)
+                || command
+                    .get_arguments()
+                    .filter(|arg| given(arg.get_id().as_str()))
+                    .any(|arg| {
+                        clashes(arg, id)
+                            || this.is_some_and(|this| clashes(this, arg.get_id().as_str()))
+                    })
+        };
+
+        macro_rules! default {
+            ($($field:ident),* $(,)?) => {$(
+                if let Some(value) = self.$field {
+                    if !explicit(stringify!($field)) {
+                        cli.$field = value.into();
+                    }
+                }
+            )*};
+        }
+
+        // Aliases given on the command line win over the config's.
+        if let Some(aliases) = self.alias {
+            let given = std::mem::take(&mut cli.alias);
+            cli.alias = aliases.into_iter().chain(given).collect();
+        }
+        if let Some(minecraft) = self.minecraft {
+            if !explicit("minecraft") {
+                cli.minecraft = vec![minecraft];
+            }
+        }
+        if let Some(urls) = self.maven_url {
+            if !explicit("maven_url") {
+                cli.maven_url = urls.split(',').map(|url| url.trim().to_string()).collect();
+            }
+        }
+        default!(
+            loader,
+            channel,
+            allow_prerelease,
+            mappings,
+            include_unstable_mappings,
+            snapshot,
+            qfapi_fallback,
+            fabric_api_fallback,
+            fail_if_qfapi_missing,
+            no_qfapi,
+            no_qsl,
+            components,
+            kotlin,
+            hashed,
+            best_effort,
+            loader_version,
+            loom_version,
+            loom_major,
+            loom_snapshot,
+            loom_range,
+            loom_plugin_id,
+            minecraft_module,
+            maven_repo,
+            mappings_version,
+            mappings_build,
+            qfapi_version,
+            strict,
+            verify,
+            quiet,
+            self_check,
+            color,
+            format,
+            json_pretty,
+            timeout,
+            retries,
+            max_redirects,
+            jobs,
+            user_agent,
+            user_agent_suffix,
+            proxy,
+            respect_gradle_props,
+            meta_url,
+            snapshot_url,
+            parchment_url,
+            fabric_maven_url,
+            no_cache,
+            no_delta,
+            cache_ttl,
+        );
+    }
+}
+
+fn find() -> Option<PathBuf> {
+    let config_dir = directories::BaseDirs::new().map(|dirs| dirs.config_dir().join(FILE_NAME));
+    [Some(PathBuf::from(FILE_NAME)), config_dir]
+        .into_iter()
+        .flatten()
+        .find(|path| path.is_file())
+}
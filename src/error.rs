@@ -0,0 +1,225 @@
+use std::io;
+use std::time::Duration;
+
+/// Errors returned by [`Client`](crate::Client) and the resolution functions.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// A request failed to connect or got an error status back.
+    #[error("request to {url} failed{}", retried(*.attempts))]
+    Http {
+        url: String,
+        attempts: u32,
+        #[source]
+        source: Box<ureq::Error>,
+    },
+
+    /// A request got no response in time.
+    #[error("request to {url} timed out after {}s", .timeout.as_secs())]
+    Timeout {
+        url: String,
+        timeout: Duration,
+        #[source]
+        source: Box<ureq::Error>,
+    },
+
+    /// The server keeps rate limiting requests (429 Too Many Requests).
+    #[error("rate limited by the server for {url}{}", wait(*.retry_after))]
+    RateLimited {
+        url: String,
+        /// How long the server asked to wait, if it said.
+        retry_after: Option<Duration>,
+    },
+
+    /// A request was redirected, but redirects aren't followed.
+    #[error("request to {url} was redirected{} without following it", to(.location.as_deref()))]
+    Redirected {
+        url: String,
+        location: Option<String>,
+    },
+
+    /// A response body could not be read.
+    #[error("failed to read the response from {url}")]
+    Read {
+        url: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A response body is not in the expected format.
+    #[error("invalid response from {url}")]
+    Parse {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// A response is needed that isn't cached, while offline.
+    #[error("{url} is not cached; run once online first")]
+    NotCached { url: String },
+
+    /// A request can't be served from the cache, while offline.
+    #[error("can't request {url} while offline")]
+    Offline { url: String },
+
+    /// A request failed on the maven repository and every mirror.
+    #[error("every maven mirror failed:{}", list(.errors))]
+    Mirrors { errors: Vec<Error> },
+
+    #[error("invalid proxy URL {url}")]
+    InvalidProxy {
+        url: String,
+        #[source]
+        source: Box<ureq::Error>,
+    },
+
+    /// A maven module is not of the form `group:artifact`.
+    #[error("invalid maven module {0}")]
+    InvalidModule(String),
+
+    /// A listing that should never be empty is.
+    #[error("no {component} versions (???)")]
+    NoVersions { component: String },
+
+    /// A maven package exists, but has no versions published.
+    #[error("maven package {package} has no published versions")]
+    NoPublishedVersions { package: String },
+
+    /// No mappings are published for the Minecraft version.
+    #[error("no mappings published for Minecraft {minecraft} yet")]
+    NoMappings { minecraft: String },
+
+    /// The requested Minecraft version doesn't exist.
+    #[error("Minecraft version {version} not found{}", suggest(.suggestion.as_deref()))]
+    UnknownMinecraft {
+        version: String,
+        /// The known version closest to it, if any is close.
+        suggestion: Option<String>,
+    },
+
+    /// No stable Minecraft release matches a version requirement.
+    #[error("no stable Minecraft release matches {range}; the newest are {}", .newest.join(", "))]
+    NoMatchingMinecraft { range: String, newest: Vec<String> },
+
+    /// No loom release is on the requested `major.minor` line.
+    #[error("no loom {line} release; available lines: {}", .available.join(", "))]
+    NoLoomLine {
+        line: String,
+        available: Vec<String>,
+    },
+
+    /// No version of a component is compatible with the Minecraft version.
+    #[error("no {component} compatible with Minecraft version {minecraft}")]
+    NoCompatibleVersion {
+        component: String,
+        minecraft: String,
+    },
+
+    /// The requested mappings build doesn't exist.
+    #[error(
+        "no mappings build {build} for Minecraft version {minecraft}; available builds: {}",
+        join(.available)
+    )]
+    NoMappingsBuild {
+        build: u64,
+        minecraft: String,
+        available: Vec<u64>,
+    },
+
+    /// The resolve options contradict each other.
+    #[error("{0}")]
+    InvalidOptions(String),
+
+    /// An existing catalog is not valid TOML.
+    #[error("invalid TOML")]
+    Toml(#[from] toml_edit::TomlError),
+
+    /// An existing catalog is valid TOML, but not a valid catalog.
+    #[error("{0}")]
+    InvalidCatalog(String),
+
+    /// An output template is malformed.
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+
+    /// Resolved versions are built for a different Minecraft version.
+    #[error("incompatible versions: {}", .problems.join("; "))]
+    Incompatible { problems: Vec<String> },
+
+    /// Resolved artifacts are missing from their maven repository.
+    #[error("not published to maven: {}", .artifacts.join(", "))]
+    NotPublished { artifacts: Vec<String> },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Whether a request got a 404 Not Found response, from every mirror if
+    /// there are several.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::Http { source, .. } => matches!(**source, ureq::Error::Status(404, _)),
+            Error::Mirrors { errors } => errors.iter().all(Error::is_not_found),
+            _ => false,
+        }
+    }
+
+    /// Whether a request failed to reach the server or to transfer the
+    /// response, rather than being refused or malformed.
+    pub fn is_connection_failure(&self) -> bool {
+        match self {
+            Error::Http { source, .. } => crate::client::is_connection_failure(source),
+            _ => false,
+        }
+    }
+}
+
+fn wait(retry_after: Option<Duration>) -> String {
+    match retry_after {
+        Some(retry_after) => format!("; try again in {}s", retry_after.as_secs()),
+        None => "; try again later".to_string(),
+    }
+}
+
+fn to(location: Option<&str>) -> String {
+    match location {
+        Some(location) => format!(" to {location}"),
+        None => String::new(),
+    }
+}
+
+fn suggest(suggestion: Option<&str>) -> String {
+    match suggestion {
+        Some(suggestion) => format!("; did you mean {suggestion}?"),
+        None => String::new(),
+    }
+}
+
+fn retried(attempts: u32) -> String {
+    if attempts > 1 {
+        format!(" after {attempts} attempts")
+    } else {
+        String::new()
+    }
+}
+
+/// One line per error, each followed by its causes.
+fn list(errors: &[Error]) -> String {
+    let mut list = String::new();
+    for err in errors {
+        list.push_str(&format!("\n  - {err}"));
+        let mut source = std::error::Error::source(err);
+        while let Some(cause) = source {
+            list.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+    }
+    list
+}
+
+fn join(builds: &[u64]) -> String {
+    builds
+        .iter()
+        .map(|build| build.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
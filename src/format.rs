@@ -0,0 +1,1104 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::Serialize;
+
+use crate::platform::Library;
+use crate::{Components, Error, Lookup, MappingsSource, Platform, Repository, Result, Versions};
+
+/// A library that is only emitted if it was looked up.
+pub struct OptionalLibrary<'a> {
+    pub lookup: &'a Lookup,
+    /// Catalog alias and version ref.
+    pub alias: &'static str,
+    pub module: &'static str,
+    /// Human-readable name used in comments.
+    pub name: &'static str,
+    /// Maven repository the library was taken from.
+    pub repository: Repository,
+    /// Name of the library this one stands in for, if any.
+    pub replaces: Option<&'static str>,
+}
+
+impl Versions {
+    /// The platform whose API `qfapi` is: Fabric if Fabric API stands in for
+    /// Quilted Fabric API.
+    pub fn api_platform(&self) -> Platform {
+        if self.fabric_api {
+            Platform::Fabric
+        } else {
+            self.platform
+        }
+    }
+
+    /// Whether loom was left out after its lookup failed, with
+    /// [`ResolveOptions::best_effort`](crate::ResolveOptions::best_effort).
+    pub fn loom_failed(&self) -> bool {
+        self.loom.is_empty()
+            && self
+                .failures
+                .iter()
+                .any(|failure| failure.starts_with("loom: "))
+    }
+
+    /// The optional libraries, in output order.
+    pub fn optional_libraries(&self) -> [OptionalLibrary<'_>; 3] {
+        let api_platform = self.api_platform();
+        let api = api_platform.api_library();
+        [
+            OptionalLibrary {
+                lookup: &self.qfapi,
+                alias: api.alias,
+                module: api.module,
+                name: api_platform.api_name(),
+                repository: self.repositories.qfapi,
+                replaces: self.fabric_api.then(|| self.platform.api_name()),
+            },
+            OptionalLibrary {
+                lookup: &self.qsl,
+                alias: "qsl",
+                module: "org.quiltmc:qsl",
+                name: "Quilt Standard Libraries",
+                repository: self.repositories.qsl,
+                replaces: None,
+            },
+            OptionalLibrary {
+                lookup: &self.qkl,
+                alias: "qkl",
+                module: "org.quiltmc.quilt-kotlin-libraries:quilt-kotlin-libraries",
+                name: "Quilt Kotlin Libraries",
+                repository: self.repositories.qkl,
+                replaces: None,
+            },
+        ]
+    }
+
+    /// The libraries of the version catalog beyond the required ones: the
+    /// optional libraries, then the ones only other tools consume, that a mod
+    /// doesn't depend on.
+    pub fn catalog_libraries(&self) -> impl Iterator<Item = OptionalLibrary<'_>> {
+        let hashed = OptionalLibrary {
+            lookup: &self.hashed,
+            alias: "quilt_hashed",
+            module: "org.quiltmc:hashed",
+            name: "Hashed Mappings",
+            repository: self.repositories.hashed,
+            replaces: None,
+        };
+        self.optional_libraries().into_iter().chain([hashed])
+    }
+}
+
+/// Notes that loom is left out, for a commented plugin declaration to follow.
+fn loom_note(platform: Platform) -> String {
+    format!(
+        "{} Loom couldn't be looked up; check manually.",
+        platform.name()
+    )
+}
+
+/// Notes that a library stands in for one without a compatible release.
+fn substitute_note(name: &str, replaces: Option<&str>) -> Option<String> {
+    replaces
+        .map(|replaces| format!("{name} in place of {replaces}, which has no compatible release."))
+}
+
+impl Versions {
+    /// Catalog alias and module of the mappings.
+    pub fn mappings_library(&self) -> (&'static str, String) {
+        match self.mappings_source {
+            MappingsSource::Platform => {
+                let Library { alias, module } = self.platform.mappings_library();
+                (alias, module.to_string())
+            }
+            MappingsSource::Parchment => (
+                "parchment",
+                format!("org.parchmentmc.data:parchment-{}", self.minecraft),
+            ),
+        }
+    }
+}
+
+/// Custom aliases and plugin ids for the entries of a version catalog.
+///
+/// Each library's alias is also the name of its `[versions]` entry.
+#[derive(Default, Debug, Clone)]
+pub struct CatalogNaming {
+    /// Aliases to use, keyed by the default alias they replace, e.g.
+    /// `quilt_loader` to `quiltLoader`.
+    pub aliases: BTreeMap<String, String>,
+    /// Plugin id to declare loom under, for setups that relocate it.
+    pub loom_id: Option<String>,
+    /// Module to declare Minecraft as, for toolchains that publish it under
+    /// other coordinates.
+    pub minecraft_module: Option<String>,
+}
+
+impl CatalogNaming {
+    /// The alias to use in place of `default`.
+    pub fn alias<'a>(&'a self, default: &'a str) -> &'a str {
+        self.aliases.get(default).map_or(default, String::as_str)
+    }
+
+    /// The plugin id to declare loom under, the platform's by default.
+    pub fn loom_id(&self, platform: Platform) -> &str {
+        self.loom_id.as_deref().unwrap_or(platform.loom_plugin().id)
+    }
+
+    /// The module to declare Minecraft as, `com.mojang:minecraft` by default.
+    pub fn minecraft_module(&self) -> &str {
+        self.minecraft_module
+            .as_deref()
+            .unwrap_or("com.mojang:minecraft")
+    }
+}
+
+/// An output format of [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Gradle version catalog (`libs.versions.toml`).
+    #[default]
+    Catalog,
+    /// JSON object of the resolved versions.
+    Json,
+    /// Kotlin DSL snippets for `build.gradle.kts`.
+    Kotlin,
+    /// Groovy `build.gradle` using the version catalog.
+    BuildGradle,
+    /// `gradle.properties` entries.
+    Properties,
+    /// `pluginManagement` block for `settings.gradle`.
+    Settings,
+    /// GitHub Actions step outputs.
+    Github,
+    /// JSON list of the dependencies for Renovate's custom managers.
+    Renovate,
+    /// Shell `export` lines.
+    Env,
+}
+
+/// Options of [`render`] that only some formats use.
+#[derive(Default, Debug, Clone)]
+pub struct RenderOptions {
+    /// Aliases of the catalog, also used by `build.gradle`'s accessors, and
+    /// the plugin id loom is declared under wherever it is applied.
+    pub naming: CatalogNaming,
+    /// Whether the catalog declares loom as a range of its patch releases,
+    /// like `1.7.+`, instead of pinning the resolved version.
+    pub loom_range: bool,
+    /// Whether JSON is pretty-printed rather than kept on one line.
+    pub pretty_json: bool,
+    /// Components the catalog, JSON and Renovate formats emit; the others
+    /// emit every one that was resolved.
+    pub components: Components,
+}
+
+/// Formats the versions in `format`.
+pub fn render(versions: &Versions, format: Format, options: &RenderOptions) -> String {
+    let versions = &leave_out(versions, options.components);
+    match format {
+        Format::Catalog => {
+            let versions = Versions {
+                loom: if options.loom_range {
+                    patch_range(&versions.loom)
+                } else {
+                    versions.loom.clone()
+                },
+                ..versions.clone()
+            };
+            catalog(&versions, &options.naming, options.components.minecraft)
+        }
+        Format::Json => {
+            let versions = Versions {
+                minecraft: if options.components.minecraft {
+                    versions.minecraft.clone()
+                } else {
+                    String::new()
+                },
+                ..versions.clone()
+            };
+            if options.pretty_json {
+                serde_json::to_string_pretty(&versions)
+            } else {
+                serde_json::to_string(&versions)
+            }
+            .expect("versions serialize to JSON")
+        }
+        Format::Kotlin => format_kotlin_build(versions, &options.naming),
+        Format::BuildGradle => format_gradle_build(versions, &options.naming),
+        Format::Properties => format_gradle_properties(versions),
+        Format::Settings => format_gradle_settings(versions, &options.naming),
+        Format::Github => format_github_output(versions),
+        Format::Renovate => format_renovate(versions, &options.naming, options.pretty_json),
+        Format::Env => format_env(versions),
+    }
+}
+
+/// Empties the versions of the components left out of `components`, apart
+/// from Minecraft's, which the mappings may be named after.
+fn leave_out(versions: &Versions, components: Components) -> Versions {
+    let kept = |kept: bool, version: &String| if kept { version.clone() } else { String::new() };
+    Versions {
+        loader: kept(components.loader, &versions.loader),
+        mappings: kept(components.mappings, &versions.mappings),
+        loom: kept(components.loom, &versions.loom),
+        qfapi: if components.qfapi {
+            versions.qfapi.clone()
+        } else {
+            Lookup::Skipped
+        },
+        fabric_api: components.qfapi && versions.fabric_api,
+        qsl: if components.qsl {
+            versions.qsl.clone()
+        } else {
+            Lookup::Skipped
+        },
+        ..versions.clone()
+    }
+}
+
+/// The Gradle range of every patch release of `version`'s major.minor, like
+/// `1.7.+` for `1.7.4`. Pre-releases and snapshots, which a range wouldn't
+/// pick, stay pinned.
+fn patch_range(version: &str) -> String {
+    match Version::parse(version) {
+        Ok(parsed) if parsed.pre.is_empty() => format!("{}.{}.+", parsed.major, parsed.minor),
+        _ => version.to_string(),
+    }
+}
+
+/// Escapes `value` for a TOML basic string, so an odd upstream version can't
+/// break out of its quotes.
+fn toml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Joins lines into a block preceded by a blank line, or nothing if empty.
+fn block(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("\n{}\n", lines.join("\n"))
+    }
+}
+
+/// Formats the versions as a Gradle version catalog (`libs.versions.toml`).
+///
+/// Components with an empty version are left out.
+pub fn format_gradle_catalog(versions: &Versions, naming: &CatalogNaming) -> String {
+    catalog(versions, naming, true)
+}
+
+/// Formats the catalog, with the Minecraft entries or without.
+#[rustfmt::skip]
+fn catalog(versions: &Versions, naming: &CatalogNaming, with_minecraft: bool) -> String {
+    let Versions {
+        minecraft,
+        loader,
+        mappings,
+        loom,
+        platform,
+        ..
+    } = versions;
+    let minecraft_alias = naming.alias("minecraft");
+    let Library { alias: loader_alias, module: loader_module } = platform.loader_library();
+    let loader_alias = naming.alias(loader_alias);
+    let (mappings_alias, mappings_module) = versions.mappings_library();
+    let mappings_alias = naming.alias(mappings_alias);
+    let loom_alias = naming.alias(platform.loom_plugin().alias);
+    let loom_id = toml_escape(naming.loom_id(*platform));
+
+    let mut version_entries = Vec::new();
+    let mut library_entries = Vec::new();
+    let minecraft = if with_minecraft { minecraft.as_str() } else { "" };
+    let libraries = [
+        (minecraft_alias, naming.minecraft_module(), minecraft),
+        (loader_alias, loader_module, loader),
+        (mappings_alias, &mappings_module, mappings),
+    ];
+    for (alias, module, version) in libraries.into_iter().filter(|(_, _, version)| !version.is_empty()) {
+        version_entries.push(format!(r#"{alias} = "{}""#, toml_escape(version)));
+        library_entries.push(format!(r#"{alias} = {{ module = "{module}", version.ref = "{alias}" }}"#));
+    }
+    let mut plugin_entries = Vec::new();
+    let mut optional_plugins = Vec::new();
+    if !loom.is_empty() {
+        plugin_entries.push(format!(r#"{loom_alias} = {{ id = "{loom_id}", version = "{}" }}"#, toml_escape(loom)));
+    } else if versions.loom_failed() {
+        optional_plugins.push(format!("# {}", loom_note(*platform)));
+        optional_plugins.push(format!(r#"# {loom_alias} = {{ id = "{loom_id}", version = "<version>" }}"#));
+    }
+
+    let mut optional_versions = Vec::new();
+    let mut optional_entries = Vec::new();
+    for OptionalLibrary { lookup, alias, module, name, replaces, .. } in versions.catalog_libraries() {
+        let alias = naming.alias(alias);
+        let entry = format!(r#"{alias} = {{ module = "{module}", version.ref = "{alias}" }}"#);
+        match lookup {
+            Lookup::Found(version) => {
+                optional_versions.extend(substitute_note(name, replaces).map(|note| format!("# {note}")));
+                optional_versions.push(format!(r#"{alias} = "{}""#, toml_escape(version)));
+                optional_entries.push(entry);
+            }
+            Lookup::NotFound => {
+                optional_versions.push(format!("# Compatible {name} not found; check manually."));
+                optional_entries.push(format!("# {entry}"));
+            }
+            Lookup::Skipped => {}
+        }
+    }
+
+    // A table is left out if it has no entries at all.
+    let table = |header: &str, entries: &[String], optional: &[String]| match (entries.is_empty(), optional.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(format!("{header}{}", block(optional))),
+        (false, _) => Some(format!("{header}\n{}\n{}", entries.join("\n"), block(optional))),
+    };
+    [
+        table("[versions]", &version_entries, &optional_versions),
+        table("[libraries]", &library_entries, &optional_entries),
+        table("[plugins]", &plugin_entries, &optional_plugins),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n")
+    .trim_end()
+    .to_string()
+}
+
+/// Formats the versions as Kotlin DSL snippets for `build.gradle.kts`.
+#[rustfmt::skip]
+pub fn format_kotlin_build(versions: &Versions, naming: &CatalogNaming) -> String {
+    let Versions {
+        minecraft,
+        loader,
+        mappings,
+        loom,
+        platform,
+        ..
+    } = versions;
+    let loader_module = platform.loader_library().module;
+    let (_, mappings_module) = versions.mappings_library();
+    let mappings_dependency = match versions.mappings_source {
+        MappingsSource::Platform => {
+            let classifier = platform.mappings_classifier();
+            format!(r#"mappings("{mappings_module}:{mappings}:{classifier}")"#)
+        }
+        MappingsSource::Parchment => format!(
+r#"mappings(loom.layered {{
+        officialMojangMappings()
+        parchment("{mappings_module}:{mappings}@zip")
+    }})"#
+        ),
+    };
+    let loom_id = naming.loom_id(*platform);
+    let loom_plugin = if loom.is_empty() {
+        format!(r#"// {}
+    // id("{loom_id}") version "<version>""#, loom_note(*platform))
+    } else {
+        format!(r#"id("{loom_id}") version "{loom}""#)
+    };
+
+    let mut optional_dependencies = Vec::new();
+    for OptionalLibrary { lookup, module, name, replaces, .. } in versions.optional_libraries() {
+        match lookup {
+            Lookup::Found(version) => {
+                optional_dependencies.extend(substitute_note(name, replaces).map(|note| format!("    // {note}")));
+                optional_dependencies.push(format!(r#"    modImplementation("{module}:{version}")"#));
+            }
+            Lookup::NotFound => {
+                optional_dependencies.push(format!("    // Compatible {name} not found; check manually."));
+                optional_dependencies.push(format!(r#"    // modImplementation("{module}:<version>")"#));
+            }
+            Lookup::Skipped => {}
+        }
+    }
+    let optional_dependencies = block(&optional_dependencies);
+
+    format!(
+r#"plugins {{
+    {loom_plugin}
+}}
+
+dependencies {{
+    minecraft("com.mojang:minecraft:{minecraft}")
+    {mappings_dependency}
+    modImplementation("{loader_module}:{loader}")
+{optional_dependencies}}}"#
+    )
+}
+
+/// Formats a Groovy `build.gradle` that applies loom and declares the
+/// dependencies through the version catalog's accessors.
+#[rustfmt::skip]
+pub fn format_gradle_build(versions: &Versions, naming: &CatalogNaming) -> String {
+    let platform = versions.platform;
+    let minecraft = accessor(naming.alias("minecraft"));
+    let loader = accessor(naming.alias(platform.loader_library().alias));
+    let (mappings_alias, _) = versions.mappings_library();
+    let mappings = accessor(naming.alias(mappings_alias));
+    let mappings_dependency = match versions.mappings_source {
+        MappingsSource::Platform => {
+            let classifier = platform.mappings_classifier();
+            format!(r#"mappings variantOf(libs.{mappings}) {{ classifier "{classifier}" }}"#)
+        }
+        MappingsSource::Parchment => format!(
+r#"mappings loom.layered {{
+        officialMojangMappings()
+        parchment variantOf(libs.{mappings}) {{ artifactType "zip" }}
+    }}"#
+        ),
+    };
+    let loom = accessor(naming.alias(platform.loom_plugin().alias));
+    let loom_plugin = if versions.loom.is_empty() {
+        format!("// {}\n    // alias libs.plugins.{loom}", loom_note(platform))
+    } else {
+        format!("alias libs.plugins.{loom}")
+    };
+
+    let mut optional_dependencies = Vec::new();
+    for OptionalLibrary { lookup, alias, name, replaces, .. } in versions.optional_libraries() {
+        let library = accessor(naming.alias(alias));
+        match lookup {
+            Lookup::Found(_) => {
+                optional_dependencies.extend(substitute_note(name, replaces).map(|note| format!("    // {note}")));
+                optional_dependencies.push(format!("    modImplementation libs.{library}"));
+            }
+            Lookup::NotFound => {
+                optional_dependencies.push(format!("    // Compatible {name} not found; check manually."));
+                optional_dependencies.push(format!("    // modImplementation libs.{library}"));
+            }
+            Lookup::Skipped => {}
+        }
+    }
+    let optional_dependencies = block(&optional_dependencies);
+
+    format!(
+r#"plugins {{
+    {loom_plugin}
+}}
+
+dependencies {{
+    minecraft libs.{minecraft}
+    {mappings_dependency}
+    modImplementation libs.{loader}
+{optional_dependencies}}}"#
+    )
+}
+
+/// The Gradle accessor of a catalog alias, whose `_` and `-` separators
+/// become dots: `quilt_loader` is `libs.quilt.loader`.
+fn accessor(alias: &str) -> String {
+    alias.replace(['_', '-'], ".")
+}
+
+/// Formats the `pluginManagement` block of `settings.gradle`, which makes the
+/// loom plugin available.
+#[rustfmt::skip]
+pub fn format_gradle_settings(versions: &Versions, naming: &CatalogNaming) -> String {
+    let Versions { loom, platform, .. } = versions;
+    let name = platform.name();
+    let url = platform.maven_url();
+    let loom_id = naming.loom_id(*platform);
+    let loom_plugin = if loom.is_empty() {
+        format!(r#"// {}
+        // id "{loom_id}" version "<version>""#, loom_note(*platform))
+    } else {
+        format!(r#"id "{loom_id}" version "{loom}""#)
+    };
+    let snapshots = if loom.ends_with("-SNAPSHOT") && platform.snapshot_url() != url {
+        let snapshot_url = platform.snapshot_url();
+        format!(
+r#"
+        maven {{
+            name = "{name} Snapshots"
+            url = "{snapshot_url}"
+        }}"#
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+r#"pluginManagement {{
+    repositories {{
+        maven {{
+            name = "{name}"
+            url = "{url}"
+        }}{snapshots}
+        gradlePluginPortal()
+    }}
+    plugins {{
+        {loom_plugin}
+    }}
+}}"#
+    )
+}
+
+/// Formats the versions as `gradle.properties` entries.
+#[rustfmt::skip]
+pub fn format_gradle_properties(versions: &Versions) -> String {
+    let Versions {
+        minecraft,
+        loader,
+        mappings,
+        loom,
+        ..
+    } = versions;
+    let (mappings_alias, _) = versions.mappings_library();
+
+    let mut optional_properties = Vec::new();
+    for OptionalLibrary { lookup, alias, name, replaces, .. } in versions.optional_libraries() {
+        match lookup {
+            Lookup::Found(version) => {
+                optional_properties.extend(substitute_note(name, replaces).map(|note| format!("# {note}")));
+                optional_properties.push(format!("{alias}={version}"));
+            }
+            Lookup::NotFound => {
+                optional_properties.push(format!("# Compatible {name} not found; check manually."));
+                optional_properties.push(format!("# {alias}="));
+            }
+            Lookup::Skipped => {}
+        }
+    }
+    let optional_properties = block(&optional_properties);
+
+    format!(
+r#"minecraft_version={minecraft}
+loader_version={loader}
+{mappings_alias}={mappings}
+loom_version={loom}
+{optional_properties}"#
+    )
+    .trim_end()
+    .to_string()
+}
+
+/// Formats the versions as `key=value` lines for GitHub Actions step outputs
+/// (`$GITHUB_OUTPUT`).
+///
+/// `qfapi` is empty if no compatible version was found.
+#[rustfmt::skip]
+pub fn format_github_output(versions: &Versions) -> String {
+    let Versions {
+        minecraft,
+        loader,
+        mappings,
+        loom,
+        qfapi,
+        ..
+    } = versions;
+    let qfapi = qfapi.found().unwrap_or_default();
+
+    format!(
+r#"minecraft={minecraft}
+loader={loader}
+mappings={mappings}
+loom={loom}
+qfapi={qfapi}"#
+    )
+}
+
+#[derive(Serialize)]
+struct Renovate {
+    dependencies: Vec<RenovateDependency>,
+}
+
+/// Formats the versions as shell `export` lines, like
+/// `export QUILT_LOADER='0.26.4'`, to `eval` in a script.
+///
+/// The variables are `QUILT_MINECRAFT`, `QUILT_LOADER`, `QUILT_MAPPINGS`,
+/// `QUILT_LOOM` and `QUILT_QFAPI`, whatever the platform. `QUILT_QFAPI` is
+/// exported empty if no compatible version was found.
+pub fn format_env(versions: &Versions) -> String {
+    let qfapi = versions.qfapi.found().unwrap_or_default();
+    [
+        ("MINECRAFT", versions.minecraft.as_str()),
+        ("LOADER", &versions.loader),
+        ("MAPPINGS", &versions.mappings),
+        ("LOOM", &versions.loom),
+        ("QFAPI", qfapi),
+    ]
+    .map(|(name, version)| format!("export QUILT_{name}='{}'", version.replace('\'', r"'\''")))
+    .join("\n")
+}
+
+/// A dependency as Renovate's custom managers take it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RenovateDependency {
+    dep_name: String,
+    package_name: String,
+    current_value: String,
+    datasource: &'static str,
+}
+
+/// Formats the versions as a JSON object whose `dependencies` list each
+/// maven coordinate and its version, for Renovate's custom managers.
+///
+/// Each dependency has a `depName`, which is the plugin id for loom, the
+/// `group:artifact` coordinate of its `packageName`, its `currentValue` and
+/// the `maven` datasource. Components without a version are left out, as is
+/// Minecraft, which isn't published to a maven repository.
+pub fn format_renovate(versions: &Versions, naming: &CatalogNaming, pretty: bool) -> String {
+    let library = |module: &str, version: &str| RenovateDependency {
+        dep_name: module.to_string(),
+        package_name: module.to_string(),
+        current_value: version.to_string(),
+        datasource: "maven",
+    };
+    let loom_id = naming.loom_id(versions.platform);
+    let mut dependencies = vec![
+        library(versions.platform.loader_library().module, &versions.loader),
+        library(&versions.mappings_library().1, &versions.mappings),
+        RenovateDependency {
+            dep_name: loom_id.to_string(),
+            // Plugins resolve through their marker artifact.
+            package_name: format!("{loom_id}:{loom_id}.gradle.plugin"),
+            current_value: versions.loom.clone(),
+            datasource: "maven",
+        },
+    ];
+    dependencies.retain(|dependency| !dependency.current_value.is_empty());
+    for OptionalLibrary { lookup, module, .. } in versions.catalog_libraries() {
+        if let Some(version) = lookup.found() {
+            dependencies.push(library(module, version));
+        }
+    }
+
+    let renovate = Renovate { dependencies };
+    if pretty {
+        serde_json::to_string_pretty(&renovate)
+    } else {
+        serde_json::to_string(&renovate)
+    }
+    .expect("dependencies serialize to JSON")
+}
+
+/// Substitutes the versions into the `{{name}}` placeholders of a template.
+///
+/// The placeholders are `minecraft`, `loader`, `mappings` and `loom`, and
+/// `qfapi`, `qsl` and `qkl`, which are empty without a version. Each of the
+/// latter three has a `_present` counterpart that is `true` or `false`, and
+/// `fabric_api` tells whether `qfapi` is Fabric API standing in for Quilted
+/// Fabric API.
+pub fn format_template(template: &str, versions: &Versions) -> Result<String> {
+    let optional = |lookup: &Lookup| lookup.found().unwrap_or_default().to_string();
+    let present = |lookup: &Lookup| lookup.found().is_some().to_string();
+
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| Error::InvalidTemplate("unclosed `{{`".to_string()))?;
+        let value = match rest[start + 2..start + end].trim() {
+            "minecraft" => versions.minecraft.clone(),
+            "loader" => versions.loader.clone(),
+            "mappings" => versions.mappings.clone(),
+            "loom" => versions.loom.clone(),
+            "qfapi" => optional(&versions.qfapi),
+            "qfapi_present" => present(&versions.qfapi),
+            "fabric_api" => versions.fabric_api.to_string(),
+            "qsl" => optional(&versions.qsl),
+            "qsl_present" => present(&versions.qsl),
+            "qkl" => optional(&versions.qkl),
+            "qkl_present" => present(&versions.qkl),
+            name => {
+                return Err(Error::InvalidTemplate(format!(
+                    "unknown placeholder `{name}`"
+                )))
+            }
+        };
+        output.push_str(&value);
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Repositories;
+
+    fn versions(qfapi: Lookup) -> Versions {
+        Versions {
+            platform: Platform::Quilt,
+            minecraft: "1.20.1".to_string(),
+            loom: "1.7.4".to_string(),
+            loader: "0.26.4".to_string(),
+            mappings: "1.20.1+build.23".to_string(),
+            mappings_source: MappingsSource::Platform,
+            qfapi,
+            fabric_api: false,
+            qsl: Lookup::Skipped,
+            qkl: Lookup::Skipped,
+            hashed: Lookup::Skipped,
+            repositories: Repositories::default(),
+            failures: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn catalog_with_qfapi() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+
+        assert_eq!(
+            format_gradle_catalog(&versions, &CatalogNaming::default()),
+            r#"[versions]
+minecraft = "1.20.1"
+quilt_loader = "0.26.4"
+quilt_mappings = "1.20.1+build.23"
+
+quilted_fabric_api = "7.1.0+0.89.0-1.20.1"
+
+[libraries]
+minecraft = { module = "com.mojang:minecraft", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+quilted_fabric_api = { module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }
+
+[plugins]
+quilt_loom = { id = "org.quiltmc.loom", version = "1.7.4" }"#
+        );
+    }
+
+    #[test]
+    fn catalog_without_qfapi() {
+        let versions = versions(Lookup::NotFound);
+
+        assert_eq!(
+            format_gradle_catalog(&versions, &CatalogNaming::default()),
+            r#"[versions]
+minecraft = "1.20.1"
+quilt_loader = "0.26.4"
+quilt_mappings = "1.20.1+build.23"
+
+# Compatible Quilted Fabric API not found; check manually.
+
+[libraries]
+minecraft = { module = "com.mojang:minecraft", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+# quilted_fabric_api = { module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }
+
+[plugins]
+quilt_loom = { id = "org.quiltmc.loom", version = "1.7.4" }"#
+        );
+    }
+
+    #[test]
+    fn catalog_with_loom_range() {
+        let versions = versions(Lookup::NotFound);
+        let options = RenderOptions {
+            loom_range: true,
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Catalog, &options),
+            r#"[versions]
+minecraft = "1.20.1"
+quilt_loader = "0.26.4"
+quilt_mappings = "1.20.1+build.23"
+
+# Compatible Quilted Fabric API not found; check manually.
+
+[libraries]
+minecraft = { module = "com.mojang:minecraft", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+# quilted_fabric_api = { module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }
+
+[plugins]
+quilt_loom = { id = "org.quiltmc.loom", version = "1.7.+" }"#
+        );
+        assert_eq!(patch_range("1.8.0-SNAPSHOT"), "1.8.0-SNAPSHOT");
+    }
+
+    #[test]
+    fn catalog_with_custom_coordinates() {
+        let versions = versions(Lookup::NotFound);
+        let naming = CatalogNaming {
+            loom_id: Some("com.example.loom".to_string()),
+            minecraft_module: Some("net.example:minecraft-wrapped".to_string()),
+            ..CatalogNaming::default()
+        };
+
+        assert_eq!(
+            format_gradle_catalog(&versions, &naming),
+            r#"[versions]
+minecraft = "1.20.1"
+quilt_loader = "0.26.4"
+quilt_mappings = "1.20.1+build.23"
+
+# Compatible Quilted Fabric API not found; check manually.
+
+[libraries]
+minecraft = { module = "net.example:minecraft-wrapped", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+# quilted_fabric_api = { module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }
+
+[plugins]
+quilt_loom = { id = "com.example.loom", version = "1.7.4" }"#
+        );
+    }
+
+    #[test]
+    fn catalog_without_loom() {
+        let versions = Versions {
+            loom: String::new(),
+            failures: vec!["loom: not found".to_string()],
+            ..versions(Lookup::NotFound)
+        };
+
+        assert_eq!(
+            format_gradle_catalog(&versions, &CatalogNaming::default()),
+            r#"[versions]
+minecraft = "1.20.1"
+quilt_loader = "0.26.4"
+quilt_mappings = "1.20.1+build.23"
+
+# Compatible Quilted Fabric API not found; check manually.
+
+[libraries]
+minecraft = { module = "com.mojang:minecraft", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+# quilted_fabric_api = { module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }
+
+[plugins]
+# Quilt Loom couldn't be looked up; check manually.
+# quilt_loom = { id = "org.quiltmc.loom", version = "<version>" }"#
+        );
+    }
+
+    #[test]
+    fn catalog_escapes_versions() {
+        let mut versions = versions(Lookup::Found("7.1.0\"\\\n".to_string()));
+        versions.loader = r#"0.26.4" # injected"#.to_string();
+
+        let catalog: toml_edit::DocumentMut =
+            format_gradle_catalog(&versions, &CatalogNaming::default())
+                .parse()
+                .unwrap();
+        assert_eq!(
+            catalog["versions"]["quilt_loader"].as_str(),
+            Some(r#"0.26.4" # injected"#)
+        );
+        assert_eq!(
+            catalog["versions"]["quilted_fabric_api"].as_str(),
+            Some("7.1.0\"\\\n")
+        );
+    }
+
+    #[test]
+    fn render_properties() {
+        let versions = versions(Lookup::NotFound);
+
+        assert_eq!(
+            render(&versions, Format::Properties, &RenderOptions::default()),
+            format_gradle_properties(&versions)
+        );
+    }
+
+    #[test]
+    fn render_env() {
+        let versions = versions(Lookup::NotFound);
+
+        assert_eq!(
+            render(&versions, Format::Env, &RenderOptions::default()),
+            "export QUILT_MINECRAFT='1.20.1'
+export QUILT_LOADER='0.26.4'
+export QUILT_MAPPINGS='1.20.1+build.23'
+export QUILT_LOOM='1.7.4'
+export QUILT_QFAPI=''"
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&versions, Format::Json, &RenderOptions::default()))
+                .unwrap();
+
+        assert_eq!(json["minecraft"], "1.20.1");
+        assert_eq!(json["qfapi"], "7.1.0+0.89.0-1.20.1");
+    }
+
+    #[test]
+    fn versions_read_back_from_json() {
+        let versions = Versions {
+            qsl: Lookup::NotFound,
+            ..versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()))
+        };
+        let json = render(&versions, Format::Json, &RenderOptions::default());
+        let read: Versions = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(read.platform, Platform::Quilt);
+        assert_eq!(read.qsl, Lookup::NotFound);
+        assert_eq!(read.qkl, Lookup::Skipped);
+        assert_eq!(render(&read, Format::Json, &RenderOptions::default()), json);
+    }
+
+    #[test]
+    fn catalog_with_only_given_components() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+        let options = RenderOptions {
+            components: Components {
+                minecraft: false,
+                mappings: false,
+                qfapi: false,
+                ..Components::default()
+            },
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Catalog, &options),
+            r#"[versions]
+quilt_loader = "0.26.4"
+
+[libraries]
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+
+[plugins]
+quilt_loom = { id = "org.quiltmc.loom", version = "1.7.4" }"#
+        );
+    }
+
+    #[test]
+    fn render_renovate() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+
+        assert_eq!(
+            render(&versions, Format::Renovate, &RenderOptions::default()),
+            concat!(
+                r#"{"dependencies":["#,
+                r#"{"depName":"org.quiltmc:quilt-loader","packageName":"org.quiltmc:quilt-loader","currentValue":"0.26.4","datasource":"maven"},"#,
+                r#"{"depName":"org.quiltmc:quilt-mappings","packageName":"org.quiltmc:quilt-mappings","currentValue":"1.20.1+build.23","datasource":"maven"},"#,
+                r#"{"depName":"org.quiltmc.loom","packageName":"org.quiltmc.loom:org.quiltmc.loom.gradle.plugin","currentValue":"1.7.4","datasource":"maven"},"#,
+                r#"{"depName":"org.quiltmc.quilted-fabric-api:quilted-fabric-api","packageName":"org.quiltmc.quilted-fabric-api:quilted-fabric-api","currentValue":"7.1.0+0.89.0-1.20.1","datasource":"maven"}"#,
+                "]}"
+            )
+        );
+    }
+
+    #[test]
+    fn render_kotlin_with_loom_id() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+        let options = RenderOptions {
+            naming: CatalogNaming {
+                loom_id: Some("com.example.loom".to_string()),
+                ..CatalogNaming::default()
+            },
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Kotlin, &options),
+            r#"plugins {
+    id("com.example.loom") version "1.7.4"
+}
+
+dependencies {
+    minecraft("com.mojang:minecraft:1.20.1")
+    mappings("org.quiltmc:quilt-mappings:1.20.1+build.23:intermediary-v2")
+    modImplementation("org.quiltmc:quilt-loader:0.26.4")
+
+    modImplementation("org.quiltmc.quilted-fabric-api:quilted-fabric-api:7.1.0+0.89.0-1.20.1")
+}"#
+        );
+    }
+
+    #[test]
+    fn render_settings_with_loom_id() {
+        let versions = versions(Lookup::NotFound);
+        let options = RenderOptions {
+            naming: CatalogNaming {
+                loom_id: Some("com.example.loom".to_string()),
+                ..CatalogNaming::default()
+            },
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Settings, &options),
+            r#"pluginManagement {
+    repositories {
+        maven {
+            name = "Quilt"
+            url = "https://maven.quiltmc.org/repository/release"
+        }
+        gradlePluginPortal()
+    }
+    plugins {
+        id "com.example.loom" version "1.7.4"
+    }
+}"#
+        );
+    }
+
+    #[test]
+    fn render_renovate_with_loom_id() {
+        let versions = versions(Lookup::NotFound);
+        let options = RenderOptions {
+            naming: CatalogNaming {
+                loom_id: Some("com.example.loom".to_string()),
+                ..CatalogNaming::default()
+            },
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Renovate, &options),
+            concat!(
+                r#"{"dependencies":["#,
+                r#"{"depName":"org.quiltmc:quilt-loader","packageName":"org.quiltmc:quilt-loader","currentValue":"0.26.4","datasource":"maven"},"#,
+                r#"{"depName":"org.quiltmc:quilt-mappings","packageName":"org.quiltmc:quilt-mappings","currentValue":"1.20.1+build.23","datasource":"maven"},"#,
+                r#"{"depName":"com.example.loom","packageName":"com.example.loom:com.example.loom.gradle.plugin","currentValue":"1.7.4","datasource":"maven"}"#,
+                "]}"
+            )
+        );
+    }
+
+    #[test]
+    fn render_pretty_json_in_field_order() {
+        let versions = versions(Lookup::Found("7.1.0+0.89.0-1.20.1".to_string()));
+        let options = RenderOptions {
+            pretty_json: true,
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render(&versions, Format::Json, &options),
+            r#"{
+  "minecraft": "1.20.1",
+  "loader": "0.26.4",
+  "loom": "1.7.4",
+  "mappings": "1.20.1+build.23",
+  "qfapi": "7.1.0+0.89.0-1.20.1"
+}"#
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Proxy settings from Gradle's user `gradle.properties`, so requests can go
+//! through the same proxy as Gradle's.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The proxy Gradle is configured with, from the `systemProp.https.proxy*`
+/// properties, or the `systemProp.http.proxy*` ones without those.
+///
+/// There is none if the file or the properties are missing.
+pub fn proxy() -> Option<String> {
+    let path = properties_path()?;
+    let properties = match fs::read_to_string(&path) {
+        Ok(properties) => properties,
+        Err(err) => {
+            log::debug!("not reading {}: {err}", path.display());
+            return None;
+        }
+    };
+    let property = |key: &str| {
+        properties
+            .lines()
+            .map(str::trim_start)
+            .filter(|line| !line.starts_with(['#', '!']))
+            .find_map(|line| {
+                let (name, value) = line.split_once(['=', ':'])?;
+                (name.trim_end() == key).then(|| value.trim())
+            })
+            .filter(|value| !value.is_empty())
+    };
+
+    let proxy = [("https", 443), ("http", 80)]
+        .into_iter()
+        .find_map(|(scheme, default_port)| {
+            let host = property(&format!("systemProp.{scheme}.proxyHost"))?;
+            let port = property(&format!("systemProp.{scheme}.proxyPort"))
+                .map_or(default_port.to_string(), str::to_string);
+            let user = property(&format!("systemProp.{scheme}.proxyUser"));
+            let password = property(&format!("systemProp.{scheme}.proxyPassword"));
+            let credentials = match (user, password) {
+                (Some(user), password) => format!(
+                    "{}:{}@",
+                    percent_encode(user),
+                    percent_encode(password.unwrap_or_default())
+                ),
+                _ => String::new(),
+            };
+            Some(format!("http://{credentials}{host}:{port}"))
+        });
+    if proxy.is_some() {
+        log::debug!("using the proxy set in {}", path.display());
+    }
+    proxy
+}
+
+/// Escapes everything but the unreserved characters of a URL, so that a user
+/// or password containing `@`, `:` or `/` fits in a proxy URL's user info.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Gradle's user `gradle.properties`, under `GRADLE_USER_HOME` or else
+/// `~/.gradle`.
+fn properties_path() -> Option<PathBuf> {
+    let gradle_home = match env::var_os("GRADLE_USER_HOME") {
+        Some(home) => PathBuf::from(home),
+        None => directories::BaseDirs::new()?.home_dir().join(".gradle"),
+    };
+    Some(gradle_home.join("gradle.properties"))
+}
@@ -0,0 +1,92 @@
+//! The versions emitted by the last run, kept in the cache directory to show
+//! what changed since.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use quilt_latest::{Platform, Versions};
+
+/// Logs how `versions` differ from the ones the last run emitted, then
+/// records them for the next run.
+///
+/// Nothing is shown on the first run; a record that can't be read or written
+/// is treated as missing.
+pub fn show_delta(versions: &Versions) {
+    let Some(path) = path(versions.platform) else {
+        return;
+    };
+    let current = entries(versions);
+
+    let previous = fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<BTreeMap<String, String>>(&json).ok());
+    if let Some(previous) = previous {
+        let mut keys: Vec<_> = previous.keys().chain(current.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        let changes: Vec<_> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let old = previous.get(key);
+                let new = current.get(key);
+                (old != new).then(|| {
+                    let missing = || "(missing)".to_string();
+                    let old = old.cloned().unwrap_or_else(missing);
+                    let new = new.cloned().unwrap_or_else(missing);
+                    format!("  {key} {old} -> {new}")
+                })
+            })
+            .collect();
+        if !changes.is_empty() {
+            log::info!("Changed since the last run:\n{}", changes.join("\n"));
+        }
+    }
+
+    let json = serde_json::to_string(&current).expect("versions serialize to JSON");
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, json));
+    if let Err(err) = written {
+        log::debug!("failed to record the versions in {}: {err}", path.display());
+    }
+}
+
+/// The versions by catalog alias, as they are told apart in the delta.
+fn entries(versions: &Versions) -> BTreeMap<String, String> {
+    let platform = versions.platform;
+    let mut entries = BTreeMap::from([
+        ("minecraft".to_string(), versions.minecraft.clone()),
+        (
+            platform.loader_library().alias.to_string(),
+            versions.loader.clone(),
+        ),
+        (
+            versions.mappings_library().0.to_string(),
+            versions.mappings.clone(),
+        ),
+        (
+            platform.loom_plugin().alias.to_string(),
+            versions.loom.clone(),
+        ),
+    ]);
+    for library in versions.catalog_libraries() {
+        if let Some(version) = library.lookup.found() {
+            entries.insert(library.alias.to_string(), version.to_string());
+        }
+    }
+    // Components left out have no version to compare.
+    entries.retain(|_, version| !version.is_empty());
+    entries
+}
+
+/// One record per platform, as their versions don't compare.
+fn path(platform: Platform) -> Option<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))?;
+    let name = match platform {
+        Platform::Quilt => "last-versions-quilt.json",
+        Platform::Fabric => "last-versions-fabric.json",
+    };
+    Some(dirs.cache_dir().join(name))
+}
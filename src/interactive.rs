@@ -0,0 +1,88 @@
+//! Prompts for the versions to use with `--interactive`.
+
+use std::io::{self, IsTerminal};
+
+use anyhow::Result;
+use dialoguer::Select;
+use quilt_latest::{Client, Error, MappingsSource, MetaEntry, ResolveOptions};
+
+/// How many of the newest versions are offered.
+const CHOICES: usize = 10;
+
+/// Prompts for the Minecraft, loader and mappings versions that `options`
+/// doesn't pin yet, and pins the chosen ones.
+pub fn choose(client: &Client, options: &mut ResolveOptions) -> Result<()> {
+    // The prompts are drawn on stderr and read from stdin, so stdout can
+    // still be redirected to a file.
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(Error::InvalidOptions("--interactive needs a terminal".to_string()).into());
+    }
+
+    // A version, line, keyword or range given on the command line is resolved
+    // as without `--interactive`, so only an unspecified one is prompted for.
+    let games = client.meta("game")?;
+    let minecraft = if options.minecraft.is_none() && options.minecraft_range.is_none() {
+        let labels: Vec<String> = games
+            .iter()
+            .map(|entry| {
+                if entry.is_stable() {
+                    format!("{} (stable)", entry.version)
+                } else {
+                    entry.version.clone()
+                }
+            })
+            .collect();
+        let default = if options.snapshot {
+            Some(0)
+        } else {
+            games.iter().position(MetaEntry::is_stable)
+        };
+        let index = select("Minecraft version", &labels, default)?;
+        games[index].version.clone()
+    } else {
+        quilt_latest::select_minecraft(&games, options)?
+    };
+    options.minecraft = Some(minecraft.clone());
+
+    if options.loader_version.is_none() {
+        let loaders: Vec<String> = client
+            .meta("loader")?
+            .into_iter()
+            .map(|v| v.version)
+            .collect();
+        let default = loaders
+            .iter()
+            .position(|v| options.prerelease_loader || !v.contains('-'));
+        let index = select("Loader version", &loaders, default)?;
+        options.loader_version = Some(loaders[index].clone());
+    }
+
+    if options.mappings_version.is_none() && options.mappings_build.is_none() {
+        let mappings: Vec<String> = match options.mappings {
+            MappingsSource::Platform => client
+                .meta(options.platform.mappings_endpoint(&minecraft))?
+                .into_iter()
+                .map(|v| v.version)
+                .collect(),
+            MappingsSource::Parchment => client.parchment(&minecraft)?,
+        };
+        // Without any, resolving reports that nothing is compatible.
+        if !mappings.is_empty() {
+            let index = select("Mappings version", &mappings, Some(0))?;
+            options.mappings_version = Some(mappings[index].clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Offers the first [`CHOICES`] of `items`, returning the index of the chosen one.
+fn select(prompt: &str, items: &[String], default: Option<usize>) -> Result<usize> {
+    let items = &items[..items.len().min(CHOICES)];
+    let default = default.filter(|&index| index < items.len()).unwrap_or(0);
+    Ok(Select::new()
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact()?)
+}
@@ -0,0 +1,1004 @@
+//! Resolves the latest versions of the Quilt or Fabric toolchain for a
+//! Minecraft version and formats them for use in a Gradle build.
+
+use std::thread::{self, ScopedJoinHandle};
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+mod cache;
+mod client;
+mod compat;
+mod error;
+mod format;
+mod merge;
+mod platform;
+mod qfapi;
+mod verify;
+
+pub use cache::{CacheMode, DEFAULT_CACHE_TTL};
+pub use client::{
+    Client, ClientBuilder, Map, MavenPackage, MavenRepo, MavenVersioning, MavenVersions, MetaEntry,
+    Published, Repository, DEFAULT_JOBS, DEFAULT_REDIRECTS, DEFAULT_RETRIES, DEFAULT_TIMEOUT,
+    MAVEN_URL, META_URL, PARCHMENT_URL, SNAPSHOT_URL,
+};
+pub use compat::incompatibilities;
+pub use error::{Error, Result};
+pub use format::{
+    format_env, format_github_output, format_gradle_build, format_gradle_catalog,
+    format_gradle_properties, format_gradle_settings, format_kotlin_build, format_renovate,
+    format_template, render, CatalogNaming, Format, OptionalLibrary, RenderOptions,
+};
+pub use merge::{diff_gradle_catalog, merge_gradle_catalog, CatalogChange};
+pub use platform::{Library, Platform, Plugin, FABRIC_MAVEN_URL, FABRIC_META_URL};
+pub use verify::verify;
+
+/// The resolved versions of every component in a Quilt or Fabric project.
+///
+/// Fields serialize in declaration order, which is kept stable so that JSON
+/// output diffs cleanly between runs. They deserialize back from it, with the
+/// fields left out taking their defaults.
+///
+/// The components left out with [`ResolveOptions::components`] are empty, and
+/// empty versions aren't serialized.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Versions {
+    #[serde(default, skip_serializing_if = "Platform::is_quilt")]
+    pub platform: Platform,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub minecraft: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub loader: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub loom: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub mappings: String,
+    #[serde(default, skip_serializing_if = "MappingsSource::is_platform")]
+    pub mappings_source: MappingsSource,
+    /// Quilted Fabric API, or Fabric API on Fabric.
+    #[serde(default, skip_serializing_if = "Lookup::is_skipped")]
+    pub qfapi: Lookup,
+    /// Whether `qfapi` is Fabric API standing in for a Quilted Fabric API
+    /// that isn't published for the Minecraft version.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fabric_api: bool,
+    #[serde(default, skip_serializing_if = "Lookup::is_skipped")]
+    pub qsl: Lookup,
+    #[serde(default, skip_serializing_if = "Lookup::is_skipped")]
+    pub qkl: Lookup,
+    /// Quilt's hashed mappings, published once per Minecraft version.
+    #[serde(default, skip_serializing_if = "Lookup::is_skipped")]
+    pub hashed: Lookup,
+    /// The maven repositories the components were taken from.
+    #[serde(skip)]
+    pub repositories: Repositories,
+    /// Lookups left out after failing, with `best_effort`; each names the
+    /// component and the error.
+    #[serde(skip)]
+    pub failures: Vec<String>,
+}
+
+/// The maven repository each component of [`Versions`] was taken from, which
+/// [`MavenRepo::Both`] and [`ResolveOptions::loom_snapshot`] leave open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Repositories {
+    pub loom: Repository,
+    /// Fabric's when Fabric API stands in for Quilted Fabric API.
+    pub qfapi: Repository,
+    pub qsl: Repository,
+    pub qkl: Repository,
+    pub hashed: Repository,
+}
+
+/// Where the mappings come from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MappingsSource {
+    /// The platform's own mappings: Quilt Mappings, or Yarn on Fabric.
+    #[default]
+    Platform,
+    /// Parchment parameter names and javadocs layered over Mojang's mappings.
+    Parchment,
+}
+
+impl MappingsSource {
+    pub fn is_platform(&self) -> bool {
+        *self == MappingsSource::Platform
+    }
+}
+
+/// The outcome of resolving an optional component.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Lookup {
+    /// The component was not asked for and is left out of the output.
+    #[default]
+    Skipped,
+    /// No version compatible with the Minecraft version exists.
+    NotFound,
+    Found(String),
+}
+
+impl Lookup {
+    pub fn found(&self) -> Option<&str> {
+        match self {
+            Lookup::Found(version) => Some(version),
+            Lookup::Skipped | Lookup::NotFound => None,
+        }
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        *self == Lookup::Skipped
+    }
+}
+
+impl From<Option<String>> for Lookup {
+    fn from(version: Option<String>) -> Lookup {
+        version.map_or(Lookup::NotFound, Lookup::Found)
+    }
+}
+
+/// Serializes as the version, or `null` if there is none.
+impl Serialize for Lookup {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.found().serialize(serializer)
+    }
+}
+
+/// Deserializes from a version, or `null` for none; a missing field is
+/// [`Lookup::Skipped`] with `#[serde(default)]`.
+impl<'de> Deserialize<'de> for Lookup {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Lookup, D::Error> {
+        Option::<String>::deserialize(deserializer).map(Lookup::from)
+    }
+}
+
+/// The components to resolve or emit, all of them by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Components {
+    /// Minecraft is resolved anyway, as the other components are matched to
+    /// it, so this only decides whether it is emitted.
+    pub minecraft: bool,
+    pub loader: bool,
+    pub mappings: bool,
+    pub loom: bool,
+    /// The API, (Quilted) Fabric API.
+    pub qfapi: bool,
+    /// Quilt Standard Libraries.
+    pub qsl: bool,
+}
+
+impl Default for Components {
+    fn default() -> Components {
+        Components {
+            minecraft: true,
+            loader: true,
+            mappings: true,
+            loom: true,
+            qfapi: true,
+            qsl: true,
+        }
+    }
+}
+
+/// Controls how [`resolve`] selects versions.
+#[derive(Default, Debug, Clone)]
+pub struct ResolveOptions {
+    /// Mod loader to resolve the toolchain of.
+    pub platform: Platform,
+    /// Mappings to resolve.
+    pub mappings: MappingsSource,
+    /// Minecraft version to target, or a `major.minor` line to target the
+    /// latest release of; defaults to the latest version. `latest` and
+    /// `latest-snapshot` pick the latest version as without one, ignoring
+    /// `snapshot` and `minecraft_range`.
+    pub minecraft: Option<String>,
+    /// Without `minecraft`, target the newest stable release matching this
+    /// requirement instead of the latest version.
+    pub minecraft_range: Option<VersionReq>,
+    /// Consider snapshots when defaulting to the latest Minecraft version.
+    pub snapshot: bool,
+    /// Warn if the targeted Minecraft version is more than this many stable
+    /// releases behind the latest one.
+    pub outdated_releases: Option<usize>,
+    /// Consider beta and release candidate loaders.
+    pub prerelease_loader: bool,
+    /// Consider mappings builds the meta API marks as unstable.
+    pub unstable_mappings: bool,
+    /// If no API is built for the exact Minecraft version, use the newest one
+    /// built for the same major and minor version.
+    pub qfapi_fallback: bool,
+    /// On Quilt, use Fabric API if no compatible Quilted Fabric API exists.
+    pub fabric_api_fallback: bool,
+    /// Fail with [`Error::NoCompatibleVersion`] if no compatible API exists,
+    /// even after the fallbacks, instead of returning [`Lookup::NotFound`].
+    /// The API must then be looked up.
+    pub require_qfapi: bool,
+    /// Don't look up the API, (Quilted) Fabric API.
+    pub skip_qfapi: bool,
+    /// Don't look up Quilt Standard Libraries.
+    pub skip_qsl: bool,
+    /// Look up Quilt Kotlin Libraries. Ignored on Fabric.
+    pub kotlin: bool,
+    /// Look up Quilt's hashed mappings. Ignored on Fabric.
+    pub hashed: bool,
+    /// Leave out loom or an optional library whose lookup fails, listing it
+    /// in [`Versions::failures`], instead of failing. Minecraft, the loader
+    /// and the mappings stay required.
+    pub best_effort: bool,
+    /// Maven repositories to take loom and the libraries from.
+    pub maven_repo: MavenRepo,
+    /// Use the newest loom from the snapshot repository, whatever
+    /// `maven_repo` is.
+    pub loom_snapshot: bool,
+    /// Loader version to use instead of the latest one.
+    pub loader_version: Option<String>,
+    /// Loom version to use instead of the latest one.
+    pub loom_version: Option<String>,
+    /// Without `loom_version`, use the newest loom of this `major.minor` line,
+    /// e.g. `1.6`, instead of the latest one.
+    pub loom_major: Option<String>,
+    /// Mappings version to use instead of the latest one.
+    pub mappings_version: Option<String>,
+    /// Build number of the Quilt Mappings or Yarn to use instead of the
+    /// latest one.
+    pub mappings_build: Option<u64>,
+    /// API version to use instead of the latest compatible one.
+    pub qfapi_version: Option<String>,
+    /// Components to resolve; the ones left out aren't fetched.
+    pub components: Components,
+}
+
+impl Versions {
+    /// Resolves the latest compatible version of every component for
+    /// `minecraft`, or for the latest Minecraft version without one, like
+    /// [`resolve`] does for `options.minecraft`, which is ignored.
+    pub fn resolve_latest(
+        client: &Client,
+        minecraft: Option<&str>,
+        options: &ResolveOptions,
+    ) -> Result<Versions> {
+        let options = ResolveOptions {
+            minecraft: minecraft.map(String::from),
+            ..options.clone()
+        };
+        resolve(client, &options)
+    }
+}
+
+/// Resolves the latest compatible version of every component.
+pub fn resolve(client: &Client, options: &ResolveOptions) -> Result<Versions> {
+    resolve_in(client, options, None)
+}
+
+/// Resolves like [`resolve`], but picks the Minecraft version from `games`,
+/// the already fetched `game` meta endpoint, so that resolving for several
+/// Minecraft versions fetches it only once.
+pub fn resolve_with_games(
+    client: &Client,
+    options: &ResolveOptions,
+    games: &[MetaEntry],
+) -> Result<Versions> {
+    resolve_in(client, options, Some(games))
+}
+
+/// Fetches the platform's mappings builds for a Minecraft version, newest
+/// first.
+///
+/// If there are none, the Minecraft version is looked up, so that one that
+/// doesn't exist is an [`Error::UnknownMinecraft`] rather than
+/// [`Error::NoMappings`].
+pub fn list_mappings(
+    client: &Client,
+    platform: Platform,
+    minecraft: &str,
+) -> Result<Vec<MetaEntry>> {
+    match mappings_entries(client, platform, minecraft) {
+        Ok(entries) if !entries.is_empty() => Ok(entries),
+        Ok(_) | Err(Error::NoMappings { .. }) => {
+            let games = client.meta("game")?;
+            if !games.iter().any(|v| v.version == minecraft) {
+                return Err(Error::UnknownMinecraft {
+                    version: minecraft.to_string(),
+                    suggestion: closest(&games, minecraft),
+                });
+            }
+            Err(Error::NoMappings {
+                minecraft: minecraft.to_string(),
+            })
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn resolve_in(
+    client: &Client,
+    options: &ResolveOptions,
+    games: Option<&[MetaEntry]>,
+) -> Result<Versions> {
+    let platform = options.platform;
+    let quilt = platform.is_quilt();
+    if options.require_qfapi && (options.skip_qfapi || !options.components.qfapi) {
+        return Err(Error::InvalidOptions(format!(
+            "{} can't be required without being looked up",
+            platform.api_name()
+        )));
+    }
+
+    thread::scope(|s| {
+        // These don't depend on the Minecraft version, so they are fetched in
+        // the background while the Minecraft version and mappings resolve.
+        let components = options.components;
+        let loom_repo = if options.loom_snapshot {
+            MavenRepo::Snapshot
+        } else {
+            options.maven_repo
+        };
+        let loader = components.loader.then(|| s.spawn(|| client.meta("loader")));
+        let loom = components
+            .loom
+            .then(|| s.spawn(move || client.maven_in(loom_repo, platform.loom_package())));
+        let qfapi = (components.qfapi && !options.skip_qfapi)
+            .then(|| s.spawn(|| client.maven_in(options.maven_repo, platform.api_package())));
+        let qsl = (quilt && components.qsl && !options.skip_qsl)
+            .then(|| s.spawn(|| client.maven_in(options.maven_repo, "org.quiltmc.qsl")));
+        let qkl = (quilt && options.kotlin).then(|| {
+            s.spawn(|| {
+                client.maven_in(
+                    options.maven_repo,
+                    "org.quiltmc.quilt-kotlin-libraries.quilt-kotlin-libraries",
+                )
+            })
+        });
+        let hashed = (quilt && options.hashed)
+            .then(|| s.spawn(|| client.maven_in(options.maven_repo, "org.quiltmc.hashed")));
+
+        let fetched;
+        let games = match games {
+            Some(games) => games,
+            None => {
+                fetched = client.meta("game")?;
+                &fetched
+            }
+        };
+        let minecraft = select_minecraft(games, options)?;
+
+        let mappings_versions = || -> Result<Vec<String>> {
+            match options.mappings {
+                MappingsSource::Platform => Ok(mappings_entries(client, platform, &minecraft)?
+                    .into_iter()
+                    .map(|v| v.version)
+                    .collect()),
+                MappingsSource::Parchment => client.parchment(&minecraft),
+            }
+        };
+        let mappings = match (&options.mappings_version, options.mappings_build) {
+            _ if !components.mappings => String::new(),
+            (Some(version), _) => pinned("mappings", version, mappings_versions()),
+            (None, Some(_)) if options.mappings == MappingsSource::Parchment => {
+                return Err(Error::InvalidOptions(
+                    "Parchment releases have no build numbers".to_string(),
+                ));
+            }
+            (None, Some(build)) => {
+                let entries = mappings_entries(client, platform, &minecraft)?;
+                find_mappings_build(entries, build, &minecraft)?
+            }
+            (None, None) => match options.mappings {
+                MappingsSource::Platform => {
+                    let entries = mappings_entries(client, platform, &minecraft)?;
+                    find_mappings(entries, options.unstable_mappings, &minecraft)?
+                }
+                MappingsSource::Parchment => mappings_versions()?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| no_compatible("Parchment release", &minecraft))?,
+            },
+        };
+
+        let loaders = loader.map(|loader| {
+            join(loader).map(|loaders| loaders.into_iter().map(|v| v.version).collect())
+        });
+        let loader = match (loaders, &options.loader_version) {
+            (None, _) => String::new(),
+            (Some(loaders), Some(version)) => pinned("loader", version, loaders),
+            (Some(loaders), None) => {
+                let loaders = loaders?;
+                let loader = loaders
+                    .iter()
+                    .find(|v| options.prerelease_loader || !v.contains('-'))
+                    .cloned()
+                    .ok_or_else(|| no_versions("loader"))?;
+                trace_choice(
+                    "loader",
+                    loaders.iter().map(|v| {
+                        let kind = if v.contains('-') {
+                            "a prerelease"
+                        } else {
+                            "a release"
+                        };
+                        (v.as_str(), kind.to_string())
+                    }),
+                    Some(&loader),
+                );
+                if loader.contains('-') {
+                    log::info!("Using prerelease loader ({loader})");
+                }
+                loader
+            }
+        };
+
+        let mut failures = Vec::new();
+
+        let mut published = Published::none(loom_repo);
+        let loom = match (loom, &options.loom_version) {
+            (None, _) => String::new(),
+            (Some(loom), Some(version)) => {
+                pinned("loom", version, split(join(loom), &mut published))
+            }
+            (Some(loom), None) => {
+                let loom = split(join(loom), &mut published).and_then(|versions| {
+                    match &options.loom_major {
+                        Some(line) => find_loom_line(&versions, line),
+                        None => {
+                            let loom = versions
+                                .first()
+                                .cloned()
+                                .ok_or_else(|| no_versions("loom"))?;
+                            log::trace!("loom: chose {loom} (the newest of {})", versions.len());
+                            Ok(loom)
+                        }
+                    }
+                });
+                optional(options, &mut failures, "loom", loom)?.unwrap_or_default()
+            }
+        };
+        if options.loom_snapshot && !loom.is_empty() {
+            log::info!("Using loom snapshot ({loom})");
+        }
+        let mut repositories = Repositories {
+            loom: published.repository(&loom),
+            ..Repositories::default()
+        };
+
+        let failed_before = failures.len();
+
+        let mut published = Published::none(options.maven_repo);
+        let qfapi = match (qfapi, &options.qfapi_version) {
+            (Some(qfapi), Some(version)) => {
+                let versions = split(join(qfapi), &mut published);
+                Lookup::Found(pinned(platform.api_name(), version, versions))
+            }
+            (Some(qfapi), None) => {
+                let versions = split(join(qfapi), &mut published);
+                match optional(options, &mut failures, platform.api_name(), versions)? {
+                    Some(versions) => {
+                        find_qfapi(platform, &versions, &minecraft, options.qfapi_fallback).into()
+                    }
+                    None => Lookup::NotFound,
+                }
+            }
+            (None, _) => Lookup::Skipped,
+        };
+        if let Some(version) = qfapi.found() {
+            repositories.qfapi = published.repository(version);
+        }
+        // Fabric API works on Quilt too, so it can stand in for a missing QFAPI.
+        let fabric_api = if quilt && options.fabric_api_fallback && qfapi == Lookup::NotFound {
+            let versions = client.maven_from(Repository::Fabric, Platform::Fabric.api_package());
+            optional(
+                options,
+                &mut failures,
+                Platform::Fabric.api_name(),
+                versions,
+            )?
+            .and_then(|versions| {
+                find_qfapi(
+                    Platform::Fabric,
+                    &versions,
+                    &minecraft,
+                    options.qfapi_fallback,
+                )
+            })
+        } else {
+            None
+        };
+        let (qfapi, fabric_api) = match fabric_api {
+            Some(version) => {
+                log::info!("Using Fabric API {version} in place of Quilted Fabric API");
+                repositories.qfapi = Repository::Fabric;
+                (Lookup::Found(version), true)
+            }
+            None => (qfapi, false),
+        };
+        // A failure of the API's own lookup doesn't tell whether a compatible
+        // one exists.
+        let qfapi_failed = failures.len() > failed_before;
+        if options.require_qfapi && qfapi == Lookup::NotFound && !qfapi_failed {
+            return Err(no_compatible(platform.api_name(), &minecraft));
+        }
+
+        let [(qsl, qsl_published), (qkl, qkl_published), (hashed, hashed_published)] =
+            [qsl, qkl, hashed].map(|handle| {
+                let mut published = Published::none(options.maven_repo);
+                let versions = handle.map(|handle| split(join(handle), &mut published));
+                (versions, published)
+            });
+
+        let qsl = match qsl {
+            Some(qsl) => match optional(options, &mut failures, "QSL", qsl)? {
+                Some(versions) => find_qsl(&versions, &minecraft).into(),
+                None => Lookup::NotFound,
+            },
+            None => Lookup::Skipped,
+        };
+
+        let qkl = match qkl {
+            Some(qkl) => match optional(options, &mut failures, "QKL", qkl)? {
+                Some(versions) => find_qkl(&versions, &minecraft).into(),
+                None => Lookup::NotFound,
+            },
+            None => Lookup::Skipped,
+        };
+
+        // Hashed mappings are versioned by their Minecraft version alone.
+        let hashed = match hashed {
+            Some(hashed) => match optional(options, &mut failures, "Hashed Mappings", hashed)? {
+                Some(versions) => versions.into_iter().find(|v| *v == minecraft).into(),
+                None => Lookup::NotFound,
+            },
+            None => Lookup::Skipped,
+        };
+
+        let taken_from = |lookup: &Lookup, published: &Published| {
+            lookup
+                .found()
+                .map_or_else(Repository::default, |v| published.repository(v))
+        };
+        repositories.qsl = taken_from(&qsl, &qsl_published);
+        repositories.qkl = taken_from(&qkl, &qkl_published);
+        repositories.hashed = taken_from(&hashed, &hashed_published);
+
+        Ok(Versions {
+            platform,
+            minecraft,
+            loader,
+            mappings,
+            mappings_source: options.mappings,
+            loom,
+            qfapi,
+            fabric_api,
+            qsl,
+            qkl,
+            hashed,
+            repositories,
+            failures,
+        })
+    })
+}
+
+/// Selects the Minecraft version `options` targets among `games`, the `game`
+/// meta endpoint, as [`resolve`] does.
+pub fn select_minecraft(games: &[MetaEntry], options: &ResolveOptions) -> Result<String> {
+    // The keywords spell out the default choices, for scripts.
+    let (requested, snapshot, range) = match options.minecraft.as_deref() {
+        Some("latest") => (None, false, None),
+        Some("latest-snapshot") => (None, true, None),
+        requested => (
+            requested,
+            options.snapshot,
+            options.minecraft_range.as_ref(),
+        ),
+    };
+    let minecraft = if let Some(requested) = requested {
+        let minecraft = find_minecraft(games, requested)?;
+        if let Some(limit) = options.outdated_releases {
+            warn_if_outdated(games, &minecraft, limit);
+        }
+        minecraft
+    } else if let Some(range) = range {
+        let minecraft = find_minecraft_in_range(games, range)?;
+        if let Some(limit) = options.outdated_releases {
+            warn_if_outdated(games, &minecraft, limit);
+        }
+        minecraft
+    } else {
+        let newest = || games.first().ok_or_else(|| no_versions("Minecraft"));
+        let entry = if snapshot {
+            newest()?
+        } else if let Some(entry) = games.iter().find(|v| v.is_stable()) {
+            entry
+        } else if games.iter().all(|v| !v.extra.contains_key("stable")) {
+            // The API dropping the field shouldn't leave every run failing.
+            let entry = newest()?;
+            log::warn!(
+                "The meta API doesn't tell which Minecraft versions are stable; \
+                 using the newest ({})",
+                entry.version
+            );
+            entry
+        } else {
+            return Err(no_versions("stable Minecraft"));
+        };
+        trace_choice(
+            "Minecraft",
+            games.iter().map(|v| (v.version.as_str(), stability(v))),
+            Some(&entry.version),
+        );
+
+        if entry.is_stable() || !entry.extra.contains_key("stable") {
+            log::info!("Using latest Minecraft version ({})", entry.version);
+        } else {
+            log::info!("Using latest Minecraft snapshot ({})", entry.version);
+        }
+        entry.version.clone()
+    };
+    Ok(minecraft)
+}
+
+/// Finds a Minecraft version by its exact name or, failing that, the newest
+/// stable release of a `major.minor` line, e.g. `1.20.6` for `1.20`.
+fn find_minecraft(games: &[MetaEntry], requested: &str) -> Result<String> {
+    if games.iter().any(|v| v.version == requested) {
+        return Ok(requested.to_string());
+    }
+
+    let prefix = format!("{requested}.");
+    let entry = games
+        .iter()
+        .find(|v| v.is_stable() && v.version.starts_with(&prefix))
+        .ok_or_else(|| Error::UnknownMinecraft {
+            version: requested.to_string(),
+            suggestion: closest(games, requested),
+        })?;
+    log::info!(
+        "Using latest Minecraft {requested} release ({})",
+        entry.version
+    );
+    Ok(entry.version.clone())
+}
+
+/// Finds the newest stable release matching `range`, reading releases like
+/// `1.21` as `1.21.0`.
+fn find_minecraft_in_range(games: &[MetaEntry], range: &VersionReq) -> Result<String> {
+    let releases = || games.iter().filter(|v| v.is_stable());
+    let matching = releases().find(|v| {
+        let version = match v.version.matches('.').count() {
+            1 => format!("{}.0", v.version),
+            _ => v.version.clone(),
+        };
+        Version::parse(&version).is_ok_and(|version| range.matches(&version))
+    });
+
+    match matching {
+        Some(entry) => {
+            log::info!("Using latest Minecraft {range} release ({})", entry.version);
+            Ok(entry.version.clone())
+        }
+        None => Err(Error::NoMatchingMinecraft {
+            range: range.to_string(),
+            newest: releases().take(3).map(|v| v.version.clone()).collect(),
+        }),
+    }
+}
+
+/// Finds the version closest to a mistyped `requested` one by edit distance,
+/// preferring newer versions, unless none is close enough to be meant.
+fn closest(games: &[MetaEntry], requested: &str) -> Option<String> {
+    const MAX_DISTANCE: usize = 2;
+
+    games
+        .iter()
+        .map(|v| (edit_distance(&v.version, requested), &v.version))
+        .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, version)| version.clone())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Warns if more than `limit` stable releases are newer than `minecraft`, as
+/// the toolchain may have dropped support for it.
+fn warn_if_outdated(games: &[MetaEntry], minecraft: &str, limit: usize) {
+    let newer: Vec<_> = games
+        .iter()
+        .take_while(|v| v.version != minecraft)
+        .filter(|v| v.is_stable())
+        .collect();
+    if let Some(latest) = newer.first().filter(|_| newer.len() > limit) {
+        log::warn!(
+            "Minecraft {minecraft} is {} releases behind the latest ({}); it may no longer be supported",
+            newer.len(),
+            latest.version
+        );
+    }
+}
+
+/// Finds the newest API version built for `minecraft`, optionally falling back
+/// to one built for the same release line.
+fn find_qfapi(
+    platform: Platform,
+    versions: &[String],
+    minecraft: &str,
+    fallback: bool,
+) -> Option<String> {
+    let qfapi = match qfapi::find(platform, versions, minecraft) {
+        Some(qfapi) => Some(qfapi.clone()),
+        None if fallback => qfapi::find_same_line(platform, versions, minecraft).cloned(),
+        None => None,
+    };
+    trace_choice(
+        platform.api_name(),
+        versions.iter().map(|v| {
+            let built_for = qfapi::minecraft_version(platform, v).map(String::from);
+            (v.as_str(), built_for_reason(built_for))
+        }),
+        qfapi.as_deref(),
+    );
+    qfapi
+}
+
+/// Finds the newest loom of a `major.minor` line, e.g. `1.6.12` for `1.6`.
+fn find_loom_line(versions: &[String], line: &str) -> Result<String> {
+    let prefix = format!("{line}.");
+    if let Some(loom) = versions.iter().find(|v| v.starts_with(&prefix)) {
+        log::info!("Using latest loom {line} release ({loom})");
+        return Ok(loom.clone());
+    }
+
+    let mut available = Vec::new();
+    for version in versions.iter().filter_map(|v| Version::parse(v).ok()) {
+        let line = format!("{}.{}", version.major, version.minor);
+        if !available.contains(&line) {
+            available.push(line);
+        }
+    }
+    Err(Error::NoLoomLine {
+        line: line.to_string(),
+        available,
+    })
+}
+
+/// Picks the newest mappings entry, passing over ones marked unstable unless
+/// `unstable` is set or there are only such ones.
+///
+/// Entries without a `stable` field, like Quilt Mappings', count as stable.
+fn find_mappings(entries: Vec<MetaEntry>, unstable: bool, minecraft: &str) -> Result<String> {
+    let is_unstable =
+        |v: &MetaEntry| v.extra.get("stable").and_then(|stable| stable.as_bool()) == Some(false);
+    let entry = entries
+        .iter()
+        .find(|v| unstable || !is_unstable(v))
+        .or(entries.first())
+        .ok_or_else(|| Error::NoMappings {
+            minecraft: minecraft.to_string(),
+        })?;
+
+    trace_choice(
+        "mappings",
+        entries.iter().map(|v| {
+            let kind = if is_unstable(v) { "unstable" } else { "stable" };
+            (v.version.as_str(), kind.to_string())
+        }),
+        Some(&entry.version),
+    );
+    if is_unstable(entry) {
+        log::info!("Using unstable mappings ({})", entry.version);
+    }
+    Ok(entry.version.clone())
+}
+
+/// Picks the mappings entry with the given build number.
+fn find_mappings_build(entries: Vec<MetaEntry>, build: u64, minecraft: &str) -> Result<String> {
+    if let Some(entry) = entries.iter().find(|v| v.build() == Some(build)) {
+        return Ok(entry.version.clone());
+    }
+
+    Err(Error::NoMappingsBuild {
+        build,
+        minecraft: minecraft.to_string(),
+        available: entries.iter().filter_map(MetaEntry::build).collect(),
+    })
+}
+
+/// Uses a pinned version, warning if the known `versions` don't include it.
+///
+/// The pin is used as-is if the known versions could not be fetched.
+fn pinned(name: &str, version: &str, versions: Result<Vec<String>>) -> String {
+    if versions.is_ok_and(|versions| !versions.iter().any(|v| v == version)) {
+        log::warn!("{name} {version} is not a known version; using it anyway");
+    }
+    version.to_string()
+}
+
+/// Finds the newest QSL release for `minecraft`; QSL is versioned like
+/// `6.1.2+1.20.1`.
+fn find_qsl(versions: &[String], minecraft: &str) -> Option<String> {
+    let built_for = |v: &str| v.split_once('+').map(|(_, build)| build.to_string());
+    let qsl = versions
+        .iter()
+        .find(|v| built_for(v).as_deref() == Some(minecraft))
+        .cloned();
+    trace_choice(
+        "QSL",
+        versions
+            .iter()
+            .map(|v| (v.as_str(), built_for_reason(built_for(v)))),
+        qsl.as_deref(),
+    );
+    qsl
+}
+
+/// Passes the outcome of looking up an optional component through, unless it
+/// failed with `best_effort` set; the failure is then recorded and the
+/// component left out.
+fn optional<T>(
+    options: &ResolveOptions,
+    failures: &mut Vec<String>,
+    name: &str,
+    versions: Result<T>,
+) -> Result<Option<T>> {
+    match versions {
+        Ok(versions) => Ok(Some(versions)),
+        Err(err) if options.best_effort => {
+            log::debug!("leaving out {name}: {err}");
+            failures.push(format!("{name}: {err}"));
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Finds the newest Quilt Kotlin Libraries release for `minecraft`.
+///
+/// QKL versions usually only name their Kotlin and Fabric Language Kotlin
+/// versions (`3.0.0+kt.1.9.20+flk.1.10.16`). Releases that do name a Minecraft
+/// version (`+mc.1.20.1`) must match it; otherwise the newest stable release is
+/// taken, as it is not tied to a particular Minecraft version.
+fn find_qkl(versions: &[String], minecraft: &str) -> Option<String> {
+    let built_for = |version: &str| -> Option<String> {
+        let (_, build) = version.split_once('+')?;
+        build
+            .split('+')
+            .find_map(|segment| segment.strip_prefix("mc."))
+            .map(String::from)
+    };
+
+    let is_prerelease = |version: &str| version.split('+').next().unwrap_or(version).contains('-');
+
+    let qkl = if versions.iter().any(|v| built_for(v).is_some()) {
+        versions
+            .iter()
+            .find(|v| built_for(v).as_deref() == Some(minecraft))
+            .cloned()
+    } else {
+        versions.iter().find(|v| !is_prerelease(v)).cloned()
+    };
+    trace_choice(
+        "QKL",
+        versions.iter().map(|v| {
+            let reason = match built_for(v) {
+                Some(built_for) => format!("built for Minecraft {built_for}"),
+                None if is_prerelease(v) => "a prerelease".to_string(),
+                None => "not tied to a Minecraft version".to_string(),
+            };
+            (v.as_str(), reason)
+        }),
+        qkl.as_deref(),
+    );
+    qkl
+}
+
+/// Traces, for `-vv`, how `component` came to `chosen`: each newer candidate
+/// passed over, then the pick, with the reason given alongside each candidate.
+///
+/// The reasons are only worked out when tracing.
+fn trace_choice<'a>(
+    component: &str,
+    candidates: impl IntoIterator<Item = (&'a str, String)>,
+    chosen: Option<&str>,
+) {
+    const SHOWN: usize = 20;
+
+    if !log::log_enabled!(log::Level::Trace) {
+        return;
+    }
+
+    let mut skipped = 0;
+    let mut pick = None;
+    for (version, reason) in candidates {
+        if Some(version) == chosen {
+            pick = Some((version, reason));
+            break;
+        }
+        if skipped < SHOWN {
+            log::trace!("{component}: skipped {version} ({reason})");
+        }
+        skipped += 1;
+    }
+    if skipped > SHOWN {
+        log::trace!("{component}: skipped {} more", skipped - SHOWN);
+    }
+    match pick {
+        Some((version, reason)) => log::trace!("{component}: chose {version} ({reason})"),
+        None => log::trace!("{component}: none of the {skipped} versions fit"),
+    }
+}
+
+fn stability(entry: &MetaEntry) -> String {
+    if entry.is_stable() {
+        "stable"
+    } else {
+        "unstable"
+    }
+    .to_string()
+}
+
+fn built_for_reason(minecraft: Option<String>) -> String {
+    match minecraft {
+        Some(minecraft) => format!("built for Minecraft {minecraft}"),
+        None => "no Minecraft version in its build".to_string(),
+    }
+}
+
+fn no_versions(component: &str) -> Error {
+    Error::NoVersions {
+        component: component.to_string(),
+    }
+}
+
+/// Fetches the platform's mappings builds for a Minecraft version, treating a
+/// 404 as none being published yet.
+fn mappings_entries(
+    client: &Client,
+    platform: Platform,
+    minecraft: &str,
+) -> Result<Vec<MetaEntry>> {
+    client
+        .meta(platform.mappings_endpoint(minecraft))
+        .map_err(|err| {
+            if err.is_not_found() {
+                Error::NoMappings {
+                    minecraft: minecraft.to_string(),
+                }
+            } else {
+                err
+            }
+        })
+}
+
+fn no_compatible(component: &str, minecraft: &str) -> Error {
+    Error::NoCompatibleVersion {
+        component: component.to_string(),
+        minecraft: minecraft.to_string(),
+    }
+}
+
+/// Takes the versions out of `fetched`, keeping the rest in `published` to
+/// tell which repository the chosen version is taken from.
+fn split(fetched: Result<Published>, published: &mut Published) -> Result<Vec<String>> {
+    let mut fetched = fetched?;
+    let versions = std::mem::take(&mut fetched.versions);
+    *published = fetched;
+    Ok(versions)
+}
+
+/// Waits for a background lookup, propagating any panic it raised.
+fn join<T>(handle: ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
@@ -0,0 +1,81 @@
+//! A manifest of what a run resolved and where from, written with
+//! `--lockfile` to audit which upstream state produced an output, and read
+//! back with `--pin` to reproduce it.
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use quilt_latest::{Client, Versions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct Lockfile<'a> {
+    /// When the versions were resolved, in RFC 3339 UTC.
+    resolved_at: String,
+    sources: Sources<'a>,
+    versions: &'a Versions,
+}
+
+#[derive(Serialize)]
+struct Sources<'a> {
+    meta: &'a str,
+    /// The maven repository followed by its mirrors.
+    maven: &'a [String],
+}
+
+/// Writes the manifest of `versions`, as resolved through `client`, to `path`.
+pub fn write(path: &Path, client: &Client, versions: &Versions) -> Result<()> {
+    let lockfile = Lockfile {
+        resolved_at: timestamp(SystemTime::now()),
+        sources: Sources {
+            meta: client.meta_url(),
+            maven: client.maven_urls(),
+        },
+        versions,
+    };
+    let json = serde_json::to_string_pretty(&lockfile).expect("lockfile serializes to JSON");
+    crate::write_atomic(path, &format!("{json}\n"))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The part of a manifest `--pin` reads back.
+#[derive(Deserialize)]
+struct Pinned {
+    versions: Versions,
+}
+
+/// Reads the versions pinned in the manifest at `path`.
+pub fn read(path: &Path) -> Result<Versions> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let pinned: Pinned = serde_json::from_str(&json)
+        .with_context(|| format!("invalid lockfile {}", path.display()))?;
+    Ok(pinned.versions)
+}
+
+/// Formats `time` like `2024-08-09T12:34:56Z`.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since the epoch, after Howard Hinnant's
+    // `civil_from_days`.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
@@ -1,177 +1,1579 @@
-use std::env;
+mod config;
+mod gradle;
+mod history;
+mod interactive;
+mod lockfile;
+mod progress;
+mod update;
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use semver::Version;
+use clap::builder::{NonEmptyStringValueParser, TypedValueParser};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use config::Config;
+use quilt_latest::{
+    CacheMode, CatalogChange, CatalogNaming, Client, Components, Error, Lookup, Map,
+    MappingsSource, MavenRepo, MetaEntry, Platform, RenderOptions, ResolveOptions, Versions,
+};
 use serde::Deserialize;
-use serde_json::Value;
 
-const META_URL: &str = "https://meta.quiltmc.org/v3/versions";
-const MAVEN_URL: &str = "https://maven.quiltmc.org/repository/release";
-const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// Generate a Gradle version catalog using the latest Quilt or Fabric versions.
+#[derive(Parser, Debug)]
+#[command(version, after_help = EXIT_CODES_HELP)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Minecraft version to target; equivalent to `--minecraft`
+    #[arg(value_name = "MINECRAFT", conflicts_with = "minecraft")]
+    minecraft_positional: Option<String>,
+
+    /// Mod loader to target
+    #[arg(short, long, value_enum, default_value_t = Loader::Quilt, global = true)]
+    loader: Loader,
+
+    /// Minecraft version to target, a major.minor line like 1.20 to target
+    /// its latest release, or `latest` or `latest-snapshot` [default: latest
+    /// stable]; repeat or separate with commas to generate for each one
+    #[arg(short, long, value_name = "VERSION", value_delimiter = ',')]
+    minecraft: Vec<String>,
+
+    /// Generate for each Minecraft version listed in FILE, one per line
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_parser = NonEmptyStringValueParser::new().map(PathBuf::from),
+        conflicts_with_all = ["minecraft", "minecraft_positional"]
+    )]
+    minecraft_list: Option<PathBuf>,
+
+    /// Target the Minecraft version in the `minecraft_version` property of
+    /// FILE, e.g. an existing `gradle.properties`
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["minecraft", "minecraft_positional", "minecraft_list"]
+    )]
+    from_properties: Option<PathBuf>,
+
+    /// Target the newest stable Minecraft release matching a semver
+    /// requirement, e.g. `>=1.20, <1.21`
+    #[arg(
+        long,
+        value_name = "REQ",
+        conflicts_with_all = ["minecraft", "minecraft_positional", "minecraft_list", "from_properties"]
+    )]
+    minecraft_range: Option<semver::VersionReq>,
+
+    /// How stable the versions are: `beta` also considers prerelease loaders
+    /// and unstable mappings, and `snapshot` also Minecraft snapshots and the
+    /// snapshot maven repository; the individual options apply on top
+    #[arg(long, value_enum, default_value_t = Channel::Stable)]
+    channel: Channel,
+
+    /// Consider beta and release candidate loader versions
+    #[arg(long)]
+    allow_prerelease: bool,
+
+    /// Mappings to use [default: quilt, or yarn with `--loader fabric`]
+    #[arg(long, value_enum)]
+    mappings: Option<Mappings>,
+
+    /// Use the newest mappings build, even if the meta API marks it unstable
+    #[arg(long)]
+    include_unstable_mappings: bool,
+
+    /// Default to the newest Minecraft version, even if it is a snapshot
+    #[arg(long)]
+    snapshot: bool,
+
+    /// Without a (Quilted) Fabric API for the exact Minecraft version, fall
+    /// back to the newest one for the same major.minor version
+    #[arg(long)]
+    qfapi_fallback: bool,
+
+    /// Without a compatible Quilted Fabric API, use Fabric API from the Fabric
+    /// maven repository instead (Quilt only)
+    #[arg(long, conflicts_with = "qfapi_version")]
+    fabric_api_fallback: bool,
+
+    /// Fail if no compatible (Quilted) Fabric API exists instead of leaving a
+    /// commented-out placeholder
+    #[arg(long, conflicts_with = "no_qfapi")]
+    fail_if_qfapi_missing: bool,
+
+    /// Leave (Quilted) Fabric API out of the output, without looking it up
+    #[arg(
+        long,
+        conflicts_with_all = ["qfapi_fallback", "fabric_api_fallback", "qfapi_version"]
+    )]
+    no_qfapi: bool,
+
+    /// Leave Quilt Standard Libraries out of the output (Quilt only)
+    #[arg(long)]
+    no_qsl: bool,
+
+    /// Only resolve and emit these components, separated by commas, in the
+    /// catalog, JSON and Renovate formats [default: all of them]
+    #[arg(
+        long,
+        value_enum,
+        value_name = "COMPONENT",
+        value_delimiter = ',',
+        conflicts_with_all = ["template", "merge", "check"]
+    )]
+    components: Vec<Emitted>,
+
+    /// Include Quilt Kotlin Libraries in the output (Quilt only)
+    #[arg(long)]
+    kotlin: bool,
+
+    /// Include Quilt's hashed mappings in the catalog, for tools that need
+    /// them alongside the named mappings (Quilt only)
+    #[arg(long)]
+    hashed: bool,
+
+    /// Leave out (commented) loom or an optional library whose lookup fails
+    /// instead of failing; Minecraft, the loader and the mappings stay
+    /// required
+    #[arg(long)]
+    best_effort: bool,
+
+    /// Use this loader version instead of the latest one
+    #[arg(long, value_name = "VERSION")]
+    loader_version: Option<String>,
+
+    /// Use this loom version instead of the latest one
+    #[arg(long, value_name = "VERSION")]
+    loom_version: Option<String>,
+
+    /// Use the newest loom of this major.minor line, e.g. `1.6`, instead of
+    /// the latest one
+    #[arg(long, value_name = "N.N", value_parser = parse_release_line, conflicts_with = "loom_version")]
+    loom_major: Option<String>,
+
+    /// Use the newest loom from the snapshot repository instead of the latest
+    /// release
+    #[arg(long, conflicts_with = "loom_version")]
+    loom_snapshot: bool,
+
+    /// Declare loom in the catalog as a range of patch releases, like `1.7.+`,
+    /// instead of pinning the resolved version
+    #[arg(long, conflicts_with_all = ["merge", "check"])]
+    loom_range: bool,
+
+    /// Maven repositories to take loom and the libraries from; `both` picks
+    /// the newest version across the two [default: release, or both with
+    /// `--channel snapshot`]
+    #[arg(long, value_enum, value_name = "REPO")]
+    maven_repo: Option<Repo>,
+
+    /// Use this mappings version instead of the latest one
+    #[arg(long, value_name = "VERSION")]
+    mappings_version: Option<String>,
+
+    /// Use this Quilt Mappings or Yarn build number instead of the latest one
+    #[arg(long, value_name = "N", conflicts_with = "mappings_version")]
+    mappings_build: Option<u64>,
+
+    /// Use this (Quilted) Fabric API version instead of the latest one
+    #[arg(long, value_name = "VERSION")]
+    qfapi_version: Option<String>,
+
+    /// Choose the Minecraft, loader and mappings versions from a menu of the
+    /// newest ones
+    #[arg(long)]
+    interactive: bool,
+
+    /// Fail instead of warning when a resolved version is built for a
+    /// different Minecraft version
+    #[arg(long)]
+    strict: bool,
+
+    /// Check that every resolved artifact is published to maven before
+    /// writing the output
+    #[arg(long)]
+    verify: bool,
+
+    /// When to color diagnostics; `auto` colors them on a terminal unless
+    /// NO_COLOR is set
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = Color::Auto, global = true)]
+    color: Color,
+
+    /// Only print errors and warnings
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log requests and cache use; repeat for more detail
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
 
-type Map<T> = serde_json::Map<String, T>;
+    /// Tell whether a newer release of quilt-latest is available, checking
+    /// in the background; skipped with `--offline`
+    #[arg(long, global = true)]
+    self_check: bool,
 
-#[derive(Deserialize, Debug)]
-struct MetaEntry {
-    version: String,
-    #[serde(flatten)]
-    extra: Map<Value>,
+    /// Read option defaults from FILE [default: quilt-latest.toml in the
+    /// current directory or the user config directory]
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Write the output to FILE instead of stdout; with several Minecraft
+    /// versions, `{minecraft}` in FILE is replaced by each one
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Append the output to the end of the `--output` file instead of
+    /// replacing it
+    #[arg(long, requires = "output", conflicts_with = "merge")]
+    append: bool,
+
+    /// Also write FILE, a JSON manifest of the resolved versions with when
+    /// they were resolved and the meta and maven URLs they came from
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["check", "print_versions"])]
+    lockfile: Option<PathBuf>,
+
+    /// Format the versions pinned in a `--lockfile` manifest instead of
+    /// resolving any, without touching the network
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "minecraft",
+            "minecraft_positional",
+            "minecraft_list",
+            "from_properties",
+            "minecraft_range",
+            "interactive",
+            "verify",
+            "lockfile",
+        ]
+    )]
+    pin: Option<PathBuf>,
+
+    /// Update the managed entries of an existing catalog, keeping everything
+    /// else intact; the result is written back to FILE unless `--output` is set
+    #[arg(long, value_name = "FILE", conflicts_with = "format")]
+    merge: Option<PathBuf>,
+
+    /// Compare the managed entries of an existing catalog with the latest
+    /// versions, printing what changed and failing if any did
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["merge", "format", "output"])]
+    check: Option<PathBuf>,
+
+    /// Resolve and format as usual, but print what would be written, or the
+    /// changes `--merge` would make, to stderr instead of writing any file
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Print a table of the resolved versions instead of any other output
+    #[arg(long, conflicts_with_all = ["merge", "check", "format", "output"])]
+    print_versions: bool,
+
+    /// Substitute the versions into the `{{name}}` placeholders of FILE,
+    /// e.g. `{{loom}}` or `{{qfapi_present}}`, instead of using a format
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["format", "merge", "check", "print_versions"]
+    )]
+    template: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Catalog)]
+    format: Format,
+
+    /// Pretty-print the JSON of `--format json` and `--format renovate`
+    /// instead of keeping it on one line
+    #[arg(long)]
+    json_pretty: bool,
+
+    /// Use NEW as the catalog alias and version ref in place of DEFAULT, e.g.
+    /// `quilt_loader=quiltLoader`; may be repeated
+    #[arg(long, value_name = "DEFAULT=NEW", value_parser = parse_alias)]
+    alias: Vec<(String, String)>,
+
+    /// Declare loom under this plugin id, in the catalog and the other
+    /// formats that apply it, for setups that relocate it [default: the
+    /// platform's loom id]
+    #[arg(long, value_name = "ID")]
+    loom_plugin_id: Option<String>,
+
+    /// Declare Minecraft in the catalog as the GROUP:ARTIFACT module, for
+    /// toolchains that publish it elsewhere [default: com.mojang:minecraft]
+    #[arg(long, value_name = "MODULE", value_parser = parse_module)]
+    minecraft_module: Option<String>,
+
+    /// Connect and read timeout for HTTP requests
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = quilt_latest::DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+
+    /// How many times to retry a request after a connection or server error
+    #[arg(long, global = true, value_name = "N", default_value_t = quilt_latest::DEFAULT_RETRIES)]
+    retries: u32,
+
+    /// How many redirects to follow for each request; 0 disables them
+    #[arg(long, global = true, value_name = "N", default_value_t = quilt_latest::DEFAULT_REDIRECTS)]
+    max_redirects: u32,
+
+    /// How many requests may be in flight at once
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = quilt_latest::DEFAULT_JOBS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    jobs: usize,
+
+    /// Identify as PRODUCT to the servers, ahead of quilt-latest's own
+    /// User-Agent
+    #[arg(long, global = true, value_name = "PRODUCT")]
+    user_agent: Option<String>,
+
+    /// Append STRING to the User-Agent, e.g. a contact like
+    /// `(+https://github.com/me/my-mod)` for the servers to reach you at
+    #[arg(long, global = true, value_name = "STRING")]
+    user_agent_suffix: Option<String>,
+
+    /// Proxy to connect through [default: from HTTPS_PROXY, HTTP_PROXY, ...]
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// Without `--proxy`, connect through the proxy set by the
+    /// `systemProp.https.proxyHost` family in Gradle's user `gradle.properties`
+    #[arg(long, global = true)]
+    respect_gradle_props: bool,
+
+    /// Base URL of the meta API [default: the loader's]
+    #[arg(long, global = true, value_name = "URL", env = "QUILT_META_URL")]
+    meta_url: Option<String>,
+
+    /// Base URL of the maven repository [default: the loader's]; repeat or
+    /// separate with commas to add mirrors, tried in order
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        env = "QUILT_MAVEN_URL",
+        value_delimiter = ','
+    )]
+    maven_url: Vec<String>,
+
+    /// Base URL of the snapshot maven repository used by `--loom-snapshot`
+    /// and `--maven-repo`
+    /// [default: the loader's]
+    #[arg(long, global = true, value_name = "URL", env = "QUILT_SNAPSHOT_URL")]
+    snapshot_url: Option<String>,
+
+    /// User to authenticate to the maven repository as, with `--maven-pass`
+    /// [default: from MAVEN_AUTH, as USER:PASSWORD]
+    #[arg(
+        long,
+        global = true,
+        value_name = "USER",
+        env = "MAVEN_USER",
+        requires = "maven_pass"
+    )]
+    maven_user: Option<String>,
+
+    /// Password to authenticate to the maven repository with
+    #[arg(
+        long,
+        global = true,
+        value_name = "PASSWORD",
+        env = "MAVEN_PASS",
+        hide_env_values = true,
+        requires = "maven_user"
+    )]
+    maven_pass: Option<String>,
+
+    /// Base URL of the Parchment maven repository
+    #[arg(long, global = true, value_name = "URL", env = "PARCHMENT_MAVEN_URL", default_value = quilt_latest::PARCHMENT_URL)]
+    parchment_url: String,
+
+    /// Base URL of the Fabric maven repository used by `--fabric-api-fallback`
+    #[arg(long, global = true, value_name = "URL", env = "FABRIC_MAVEN_URL", default_value = quilt_latest::FABRIC_MAVEN_URL)]
+    fabric_maven_url: String,
+
+    /// Don't read or write the response cache
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Ignore cached responses, but still cache new ones
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    refresh: bool,
+
+    /// Serve every request from the response cache without using the network
+    #[arg(long, global = true, conflicts_with_all = ["no_cache", "refresh"])]
+    offline: bool,
+
+    /// Don't log what changed since the versions the last run emitted
+    #[arg(long)]
+    no_delta: bool,
+
+    /// How long cached responses stay fresh
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = quilt_latest::DEFAULT_CACHE_TTL.as_secs())]
+    cache_ttl: u64,
 }
 
-#[derive(Deserialize, Debug)]
-struct MavenPackage {
-    versioning: MavenVersioning,
+impl Cli {
+    /// The Minecraft versions to generate for; none targets the latest one.
+    fn minecraft(&self) -> Result<Vec<String>> {
+        if let Some(path) = &self.from_properties {
+            return Ok(vec![read_minecraft_property(path)?]);
+        }
+        if let Some(path) = &self.minecraft_list {
+            let list = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let versions: Vec<String> = list
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect();
+            // Falling back to the latest version would ignore the list.
+            if versions.is_empty() {
+                return Err(invalid_options(&format!(
+                    "{} lists no Minecraft versions",
+                    path.display()
+                )));
+            }
+            return Ok(versions);
+        }
+
+        Ok(self
+            .minecraft
+            .iter()
+            .chain(&self.minecraft_positional)
+            .cloned()
+            .collect())
+    }
 }
 
-#[derive(Deserialize, Debug)]
-struct MavenVersioning {
-    versions: MavenVersions,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the available versions of a component
+    List {
+        #[command(subcommand)]
+        component: Component,
+
+        /// Order to list the versions in
+        #[arg(long, value_enum, default_value_t = Sort::Desc, global = true)]
+        sort: Sort,
+
+        /// Only list the N newest versions
+        #[arg(long, value_name = "N", global = true)]
+        latest_n: Option<usize>,
+
+        /// How to print the versions; `json` prints every field the API
+        /// returns for each one
+        #[arg(
+            id = "list_format",
+            long = "format",
+            value_enum,
+            default_value_t = ListFormat::Text,
+            global = true
+        )]
+        format: ListFormat,
+    },
+    /// Write a minimal project using the latest versions: a version catalog,
+    /// `settings.gradle` and `build.gradle`
+    Init {
+        /// Directory to write the project to, created if missing
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Minecraft version to target [default: the latest stable release]
+        #[arg(id = "init_minecraft", long = "minecraft", value_name = "VERSION")]
+        minecraft: Option<String>,
+
+        /// Overwrite the project's files if they already exist
+        #[arg(long)]
+        force: bool,
+    },
 }
 
-#[derive(Deserialize, Debug)]
-struct MavenVersions {
-    version: Vec<Version>,
+#[derive(Subcommand, Debug)]
+enum Component {
+    /// Minecraft versions, marking stable releases
+    Minecraft,
+    /// Loader versions
+    Loader,
+    /// Quilt Mappings or Yarn builds for a Minecraft version
+    Mappings {
+        /// Minecraft version to list mappings for
+        minecraft: String,
+    },
+    /// Loom versions
+    Loom,
 }
 
-#[derive(Debug)]
-struct Versions {
-    minecraft: String,
-    loom: String,
-    loader: String,
-    mappings: String,
-    qfapi: Option<String>,
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl From<Color> for anstream::ColorChoice {
+    fn from(color: Color) -> anstream::ColorChoice {
+        match color {
+            Color::Auto => anstream::ColorChoice::Auto,
+            Color::Always => anstream::ColorChoice::Always,
+            Color::Never => anstream::ColorChoice::Never,
+        }
+    }
 }
 
-struct Client {
-    agent: ureq::Agent,
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ListFormat {
+    /// One version per line
+    Text,
+    /// An array of objects with the version and the API's other fields
+    Json,
 }
 
-impl Client {
-    fn new() -> Client {
-        let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Sort {
+    /// Oldest first
+    Asc,
+    /// Newest first
+    Desc,
+}
 
-        Client { agent }
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Loader {
+    Quilt,
+    Fabric,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum Channel {
+    /// Stable Minecraft releases, release loaders and stable mappings
+    Stable,
+    /// Prerelease loaders and unstable mappings too
+    Beta,
+    /// Minecraft snapshots and the snapshot maven repository too
+    Snapshot,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Repo {
+    /// The release repository
+    Release,
+    /// The snapshot repository
+    Snapshot,
+    /// Both repositories
+    Both,
+}
+
+impl From<Repo> for MavenRepo {
+    fn from(repo: Repo) -> MavenRepo {
+        match repo {
+            Repo::Release => MavenRepo::Release,
+            Repo::Snapshot => MavenRepo::Snapshot,
+            Repo::Both => MavenRepo::Both,
+        }
     }
+}
 
-    fn meta<S: AsRef<str>>(&self, path: S) -> Result<Vec<MetaEntry>> {
-        let url = format!("{}/{}", META_URL, path.as_ref());
-        let versions: Vec<MetaEntry> = self.agent.get(&url).call()?.into_json()?;
-        Ok(versions)
+impl From<Loader> for Platform {
+    fn from(loader: Loader) -> Platform {
+        match loader {
+            Loader::Quilt => Platform::Quilt,
+            Loader::Fabric => Platform::Fabric,
+        }
     }
+}
 
-    fn maven<S: AsRef<str>>(&self, pkg: S) -> Result<Vec<Version>> {
-        let url = format!(
-            "{}/{}/maven-metadata.xml",
-            MAVEN_URL,
-            pkg.as_ref().replace('.', "/")
-        );
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Mappings {
+    /// Quilt Mappings
+    Quilt,
+    /// Yarn
+    Yarn,
+    /// Parchment layered over Mojang's mappings
+    Parchment,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Emitted {
+    Minecraft,
+    Loader,
+    Mappings,
+    Loom,
+    /// (Quilted) Fabric API
+    Qfapi,
+    /// Quilt Standard Libraries
+    Qsl,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Format {
+    /// Gradle version catalog (`libs.versions.toml`)
+    Catalog,
+    /// JSON object of the resolved versions
+    Json,
+    /// Kotlin DSL snippets for `build.gradle.kts`
+    Kotlin,
+    /// Groovy `build.gradle` using the version catalog
+    BuildGradle,
+    /// `gradle.properties` entries
+    Properties,
+    /// `pluginManagement` block for `settings.gradle`
+    Settings,
+    /// GitHub Actions step outputs, appended to `$GITHUB_OUTPUT`
+    Github,
+    /// JSON list of each maven coordinate and its version, for Renovate's
+    /// custom managers
+    Renovate,
+    /// Shell `export QUILT_*=...` lines to `eval`; `QUILT_QFAPI` is empty
+    /// without a compatible version
+    Env,
+}
+
+impl From<Format> for quilt_latest::Format {
+    fn from(format: Format) -> quilt_latest::Format {
+        match format {
+            Format::Catalog => quilt_latest::Format::Catalog,
+            Format::Json => quilt_latest::Format::Json,
+            Format::Kotlin => quilt_latest::Format::Kotlin,
+            Format::BuildGradle => quilt_latest::Format::BuildGradle,
+            Format::Properties => quilt_latest::Format::Properties,
+            Format::Settings => quilt_latest::Format::Settings,
+            Format::Github => quilt_latest::Format::Github,
+            Format::Renovate => quilt_latest::Format::Renovate,
+            Format::Env => quilt_latest::Format::Env,
+        }
+    }
+}
+
+const EXIT_FAILURE: u8 = 1;
+const EXIT_INCOMPATIBLE: u8 = 2;
+const EXIT_NETWORK: u8 = 3;
+const EXIT_USAGE: u8 = 4;
+const EXIT_OUT_OF_DATE: u8 = 5;
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Any other failure
+  2  No compatible version was found
+  3  A request failed, or needs the network while offline
+  4  Invalid arguments
+  5  The catalog given to --check is out of date";
+
+/// A catalog given to `--check` differs from the latest versions.
+#[derive(Debug)]
+struct OutOfDate(PathBuf);
+
+impl std::fmt::Display for OutOfDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is out of date", self.0.display())
+    }
+}
 
-        let text = self.agent.get(&url).call()?.into_string()?;
-        let pkg: MavenPackage = quick_xml::de::from_str(&text)?;
-        let mut versions = pkg.versioning.versions.version;
-        versions.sort();
-        Ok(versions.into_iter().rev().collect())
+impl std::error::Error for OutOfDate {}
+
+fn main() -> ExitCode {
+    let parsed = Cli::command().try_get_matches().and_then(|matches| {
+        check_subcommand_args(&matches)?;
+        Ok((Cli::from_arg_matches(&matches)?, matches))
+    });
+    let (mut cli, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let _ = err.print();
+            return if err.use_stderr() {
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    match Config::load(cli.config.as_deref()) {
+        Ok(config) => config.apply(&mut cli, &matches),
+        Err(err) => {
+            print_error(&err);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    }
+    anstream::ColorChoice::write_global(cli.color.into());
+
+    init_logger(&cli);
+
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            print_error(&err);
+            if let Some(hint) = hint(&err) {
+                anstream::eprintln!("\nHint: {hint}");
+            }
+            ExitCode::from(exit_code(&err))
+        }
+    }
+}
+
+/// Rejects the generation options given alongside a subcommand, which would
+/// be silently ignored. The global options, such as the network ones, apply to
+/// the subcommands too.
+fn check_subcommand_args(matches: &clap::ArgMatches) -> Result<(), clap::Error> {
+    let Some((name, _)) = matches.subcommand() else {
+        return Ok(());
+    };
+    let mut command = Cli::command();
+    command.build();
+    let used = command.get_arguments().find(|arg| {
+        !arg.is_global_set()
+            && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+    });
+    match used {
+        Some(arg) => {
+            let message = format!("the subcommand '{name}' cannot be used with '{arg}'");
+            Err(command.error(clap::error::ErrorKind::ArgumentConflict, message))
+        }
+        None => Ok(()),
     }
 }
 
-fn main() -> Result<()> {
-    let client = Client::new();
+/// Prints an error to stderr, in red if colors are enabled.
+fn print_error(err: &anyhow::Error) {
+    let style = anstyle::AnsiColor::Red.on_default().bold();
+    anstream::eprintln!("{style}Error:{style:#} {err:?}");
+}
 
-    // Versions from quilt meta
+/// Sends log records to stderr, at the levels chosen by `--quiet` and
+/// `--verbose` unless `RUST_LOG` overrides them.
+fn init_logger(cli: &Cli) {
+    // Dependencies only log at `-vv` and beyond.
+    let (level, dependencies) = match (cli.quiet, cli.verbose) {
+        (true, _) => (log::LevelFilter::Warn, log::LevelFilter::Warn),
+        (false, 0) => (log::LevelFilter::Info, log::LevelFilter::Warn),
+        (false, 1) => (log::LevelFilter::Debug, log::LevelFilter::Warn),
+        (false, _) => (log::LevelFilter::Trace, log::LevelFilter::Trace),
+    };
+
+    env_logger::Builder::new()
+        .filter_level(dependencies)
+        .filter_module(env!("CARGO_CRATE_NAME"), level)
+        .parse_default_env()
+        // Records go through the spinner, so colors can't be detected on the
+        // way; the choice set from `--color` decides instead.
+        .target(env_logger::Target::Pipe(Box::new(progress::Stderr)))
+        .write_style(match anstream::AutoStream::choice(&std::io::stderr()) {
+            anstream::ColorChoice::Never => env_logger::WriteStyle::Never,
+            _ => env_logger::WriteStyle::Always,
+        })
+        .format(|buf, record| {
+            let style = buf.default_level_style(record.level());
+            match record.level() {
+                log::Level::Info => writeln!(buf, "{style}{}{style:#}", record.args()),
+                log::Level::Warn => writeln!(buf, "{style}Warning:{style:#} {}", record.args()),
+                level => writeln!(
+                    buf,
+                    "{style}[{}]{style:#} {}",
+                    level.as_str().to_lowercase(),
+                    record.args()
+                ),
+            }
+        })
+        .init();
+}
+
+fn exit_code(err: &anyhow::Error) -> u8 {
+    if err.is::<OutOfDate>() {
+        return EXIT_OUT_OF_DATE;
+    }
 
-    let minecraft = if let Some(version) = env::args().nth(1) {
-        version
+    match err.downcast_ref::<Error>() {
+        Some(
+            Error::NoCompatibleVersion { .. }
+            | Error::NoMappings { .. }
+            | Error::NoMappingsBuild { .. }
+            | Error::UnknownMinecraft { .. }
+            | Error::NoMatchingMinecraft { .. }
+            | Error::NoLoomLine { .. }
+            | Error::Incompatible { .. }
+            | Error::NotPublished { .. },
+        ) => EXIT_INCOMPATIBLE,
+        Some(
+            Error::Http { .. }
+            | Error::Timeout { .. }
+            | Error::RateLimited { .. }
+            | Error::Redirected { .. }
+            | Error::Read { .. }
+            | Error::Parse { .. }
+            | Error::NotCached { .. }
+            | Error::Offline { .. }
+            | Error::Mirrors { .. },
+        ) => EXIT_NETWORK,
+        Some(Error::InvalidProxy { .. } | Error::InvalidOptions(_) | Error::InvalidTemplate(_)) => {
+            EXIT_USAGE
+        }
+        _ => EXIT_FAILURE,
+    }
+}
+
+/// Suggests how to get past a failed request.
+fn hint(err: &anyhow::Error) -> Option<&'static str> {
+    match err.downcast_ref::<Error>()? {
+        err @ Error::Http { source, .. } => match **source {
+            ureq::Error::Transport(_) if err.is_connection_failure() => Some(
+                "check your network connection, or use `--offline` to work from cached responses",
+            ),
+            ureq::Error::Status(status, _) if status >= 500 => Some(
+                "the server may be down; try again later, or use `--offline` to work from cached responses",
+            ),
+            ureq::Error::Transport(_) | ureq::Error::Status(..) => None,
+        },
+        Error::Timeout { .. } => {
+            Some("check your network connection, or raise the limit with `--timeout`")
+        }
+        Error::Redirected { .. } => Some("allow redirects with `--max-redirects`"),
+        _ => None,
+    }
+}
+
+fn run(cli: &Cli) -> Result<()> {
+    let platform = Platform::from(cli.loader);
+    let cache = if cli.offline {
+        CacheMode::Offline
+    } else if cli.no_cache {
+        CacheMode::Disabled
+    } else if cli.refresh {
+        CacheMode::Refresh
     } else {
-        let version = client
-            .meta("/game")?
+        CacheMode::Enabled
+    };
+    let (maven_url, mirrors) = match cli.maven_url.split_first() {
+        Some((maven_url, mirrors)) => (maven_url.as_str(), mirrors),
+        None => (platform.maven_url(), &[][..]),
+    };
+
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(cli.timeout))
+        .retries(cli.retries)
+        .redirects(cli.max_redirects)
+        .jobs(cli.jobs)
+        .meta_url(cli.meta_url.as_deref().unwrap_or(platform.meta_url()))
+        .maven_url(maven_url)
+        .snapshot_url(
+            cli.snapshot_url
+                .as_deref()
+                .unwrap_or(platform.snapshot_url()),
+        )
+        .parchment_url(&cli.parchment_url)
+        .fabric_maven_url(&cli.fabric_maven_url)
+        .cache(cache)
+        .cache_ttl(Duration::from_secs(cli.cache_ttl));
+    for mirror in mirrors {
+        builder = builder.maven_mirror(mirror);
+    }
+    let proxy = match &cli.proxy {
+        Some(proxy) => Some(proxy.clone()),
+        None if cli.respect_gradle_props => gradle::proxy(),
+        None => None,
+    };
+    if let Some(proxy) = &proxy {
+        builder = builder.proxy(proxy);
+    }
+    let maven_auth = match (&cli.maven_user, &cli.maven_pass) {
+        (Some(user), Some(password)) => Some((user.clone(), password.clone())),
+        _ => match std::env::var("MAVEN_AUTH") {
+            Ok(auth) => {
+                let (user, password) = auth
+                    .split_once(':')
+                    .ok_or_else(|| invalid_options("MAVEN_AUTH must be USER:PASSWORD"))?;
+                Some((user.to_string(), password.to_string()))
+            }
+            Err(_) => None,
+        },
+    };
+    if let Some((user, password)) = maven_auth {
+        builder = builder.maven_auth(&user, &password);
+    }
+    if let Some(product) = &cli.user_agent {
+        builder = builder.user_agent(product);
+    }
+    if let Some(suffix) = &cli.user_agent_suffix {
+        builder = builder.user_agent_suffix(suffix);
+    }
+    let client = builder.build()?;
+
+    let update_check = (cli.self_check && !cli.offline).then(|| update::spawn(proxy));
+
+    let result = match &cli.command {
+        Some(Command::List {
+            component,
+            sort,
+            latest_n,
+            format,
+        }) => list(&client, platform, component, *sort, *latest_n, *format),
+        Some(Command::Init {
+            dir,
+            minecraft,
+            force,
+        }) => init(&client, platform, cli, dir, minecraft.clone(), *force),
+        None => generate(&client, platform, cli),
+    };
+
+    if let Some(check) = update_check {
+        update::report(check);
+    }
+    result
+}
+
+fn list(
+    client: &Client,
+    platform: Platform,
+    component: &Component,
+    sort: Sort,
+    latest_n: Option<usize>,
+    format: ListFormat,
+) -> Result<()> {
+    // Both meta and maven listings come newest first.
+    let mut entries = match component {
+        Component::Minecraft => client.meta("game")?,
+        Component::Loader => client.meta("loader")?,
+        Component::Mappings { minecraft } => {
+            quilt_latest::list_mappings(client, platform, minecraft)?
+        }
+        // Maven has nothing but the version to tell.
+        Component::Loom => client
+            .maven(platform.loom_package())?
             .into_iter()
-            .find(|entry| entry.extra.get("stable").and_then(|v| v.as_bool()) == Some(true))
-            .map(|v| v.version)
-            .with_context(|| "no stable Minecraft versions (???)")?;
-        eprintln!("Using latest Minecraft version ({version})");
-        version
-    };
-
-    let loader = client
-        .meta("/loader")?
-        .into_iter()
-        .map(|v| v.version)
-        .find(|v| !v.contains('-'))
-        .with_context(|| "no loaders (???)")?;
-
-    let mappings = client
-        .meta(format!("/quilt-mappings/{minecraft}"))?
-        .into_iter()
-        .next()
-        .map(|v| v.version)
-        .with_context(|| format!("no mappings compatible with Minecraft version {minecraft}"))?;
-
-    // Versions from quilt maven
-
-    let loom = client
-        .maven("org.quiltmc.loom")?
-        .into_iter()
-        .next()
-        .map(|v| v.to_string())
-        .with_context(|| "no loom versions (???)")?;
-
-    let qfapi = client
-        .maven("org.quiltmc.quilted-fabric-api.quilted-fabric-api")?
-        .into_iter()
-        .find(|v| v.build.contains(&minecraft))
-        .map(|v| v.to_string());
-
-    let catalog = format_gradle_catalog(&Versions {
-        minecraft,
-        loader,
-        mappings,
-        loom,
-        qfapi,
-    });
+            .map(|version| MetaEntry {
+                version,
+                extra: Map::new(),
+            })
+            .collect(),
+    };
+    if let Some(n) = latest_n {
+        entries.truncate(n);
+    }
+    if sort == Sort::Asc {
+        entries.reverse();
+    }
 
-    println!("{catalog}");
+    if format == ListFormat::Json {
+        println!("{}", serde_json::to_string(&entries)?);
+        return Ok(());
+    }
+
+    for entry in entries {
+        if matches!(component, Component::Minecraft) && entry.is_stable() {
+            println!("{} (stable)", entry.version);
+        } else {
+            println!("{}", entry.version);
+        }
+    }
 
     Ok(())
 }
 
-#[rustfmt::skip]
-fn format_gradle_catalog(
-    Versions {
-        minecraft,
-        loader,
-        mappings,
-        loom,
-        qfapi
-    }: &Versions,
-) -> String {
-    let (qfapi_version, qfapi_lib_comment) = if let Some(qfapi) = qfapi {
-        (
-            format!(r#"quilted_fabric_api = "{qfapi}""#),
-            "".to_string()
+/// Writes a project skeleton to `dir`, refusing to replace any of its files
+/// unless `force` is set.
+fn init(
+    client: &Client,
+    platform: Platform,
+    cli: &Cli,
+    dir: &Path,
+    minecraft: Option<String>,
+    force: bool,
+) -> Result<()> {
+    let options = resolve_options(platform, cli, minecraft)?;
+    let spinner = progress::start(
+        format!("Resolving {} versions…", platform.name()),
+        !cli.quiet,
+    );
+    let versions = Versions::resolve_latest(client, options.minecraft.as_deref(), &options)?;
+    check_compatibility(cli, &versions)?;
+    warn_failures(&versions);
+    drop(spinner);
+
+    let files = [
+        "gradle/libs.versions.toml",
+        "settings.gradle",
+        "build.gradle",
+    ];
+    let existing: Vec<_> = files
+        .iter()
+        .map(|file| dir.join(file))
+        .filter(|path| path.exists())
+        .collect();
+    if !force && !existing.is_empty() {
+        let existing: Vec<_> = existing
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(invalid_options(&format!(
+            "not overwriting {}; pass --force to",
+            existing.join(", ")
+        )));
+    }
+
+    let gradle_dir = dir.join("gradle");
+    fs::create_dir_all(&gradle_dir)
+        .with_context(|| format!("failed to create {}", gradle_dir.display()))?;
+
+    let rendering = render_options(cli);
+    let mut settings = render(Format::Settings, None, &versions, &rendering)?;
+    let name = fs::canonicalize(dir)
+        .ok()
+        .and_then(|dir| Some(dir.file_name()?.to_string_lossy().into_owned()));
+    if let Some(name) = name {
+        settings.push_str(&format!("\n\nrootProject.name = \"{name}\""));
+    }
+    let contents = [
+        render(Format::Catalog, None, &versions, &rendering)?,
+        settings,
+        render(Format::BuildGradle, None, &versions, &rendering)?,
+    ];
+
+    for (file, contents) in files.iter().zip(contents) {
+        let path = dir.join(file);
+        write_output(&path, &contents, false)?;
+        log::info!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn generate(client: &Client, platform: Platform, cli: &Cli) -> Result<()> {
+    if !cli.components.is_empty()
+        && !matches!(
+            cli.format,
+            Format::Catalog | Format::Json | Format::Renovate
         )
+    {
+        return Err(invalid_options(
+            "--components only applies to the catalog, JSON and Renovate formats",
+        ));
+    }
+    let minecraft = cli.minecraft()?;
+    if minecraft.len() > 1 {
+        return generate_each(client, platform, cli, &minecraft);
+    }
+
+    let template = read_template(cli)?;
+    let versions = if let Some(path) = &cli.pin {
+        let versions = lockfile::read(path)?;
+        check_compatibility(cli, &versions)?;
+        versions
     } else {
-        (
-            "# Compatible Quilted Fabric API not found; check manually.".to_string(),
-            "# ".to_string()
-        )
+        let mut options = resolve_options(platform, cli, minecraft.into_iter().next())?;
+        if cli.interactive {
+            interactive::choose(client, &mut options)?;
+        }
+        let spinner = progress::start(
+            format!("Resolving {} versions…", platform.name()),
+            !cli.quiet,
+        );
+        let versions = Versions::resolve_latest(client, options.minecraft.as_deref(), &options)?;
+        check_compatibility(cli, &versions)?;
+        warn_failures(&versions);
+        if cli.verify {
+            quilt_latest::verify(client, &versions)?;
+        }
+        drop(spinner);
+
+        if !cli.no_delta && !cli.no_cache && !cli.dry_run && cli.check.is_none() {
+            history::show_delta(&versions);
+        }
+        versions
+    };
+
+    if cli.print_versions {
+        print_versions(&versions);
+        return Ok(());
+    }
+
+    let rendering = render_options(cli);
+
+    if let Some(path) = &cli.check {
+        let existing = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let changes = quilt_latest::diff_gradle_catalog(&existing, &versions, &rendering.naming)
+            .with_context(|| format!("failed to compare with {}", path.display()))?;
+        for change in &changes {
+            println!("{}", describe(change));
+        }
+
+        if !changes.is_empty() {
+            return Err(OutOfDate(path.clone()).into());
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = &cli.merge {
+        let existing = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let merged = quilt_latest::merge_gradle_catalog(&existing, &versions, &rendering.naming)
+            .with_context(|| format!("failed to merge into {}", path.display()))?;
+        for library in versions.catalog_libraries() {
+            if *library.lookup == Lookup::NotFound {
+                log::warn!(
+                    "Compatible {} not found; leaving it unchanged",
+                    library.name
+                );
+            }
+        }
+
+        if cli.dry_run {
+            let changes =
+                quilt_latest::diff_gradle_catalog(&existing, &versions, &rendering.naming)
+                    .with_context(|| format!("failed to compare with {}", path.display()))?;
+            if changes.is_empty() {
+                eprintln!("{} is up to date", path.display());
+            }
+            for change in &changes {
+                eprintln!("{}", describe(change));
+            }
+            return Ok(());
+        }
+
+        let output = cli.output.as_deref().unwrap_or(path);
+        write_atomic(output, &merged)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        if let Some(path) = &cli.lockfile {
+            lockfile::write(path, client, &versions)?;
+        }
+        return Ok(());
+    }
+
+    let rendered = render(cli.format, template.as_deref(), &versions, &rendering)?;
+
+    if cli.dry_run {
+        eprintln!("{rendered}");
+        return Ok(());
+    }
+
+    if cli.format == Format::Github && cli.output.is_none() {
+        write_github_output(&rendered)?;
+    } else if let Some(path) = &cli.output {
+        write_output(path, &rendered, cli.append)?;
+    } else {
+        println!("{rendered}");
+    }
+
+    if let Some(path) = &cli.lockfile {
+        lockfile::write(path, client, &versions)?;
+    }
+
+    Ok(())
+}
+
+/// Generates the output for each of several Minecraft versions, either to
+/// files named after them or to stdout under a header per version.
+///
+/// The game list is fetched once and shared by every version, and other
+/// version-independent responses like the loader's are fetched only once too,
+/// as the client remembers them.
+fn generate_each(
+    client: &Client,
+    platform: Platform,
+    cli: &Cli,
+    minecraft: &[String],
+) -> Result<()> {
+    let single = |flag: &str| invalid_options(&format!("{flag} takes a single Minecraft version"));
+    if cli.interactive {
+        return Err(single("--interactive"));
+    } else if cli.check.is_some() {
+        return Err(single("--check"));
+    } else if cli.merge.is_some() {
+        return Err(single("--merge"));
+    } else if cli.format == Format::Github {
+        return Err(single("--format github"));
+    } else if cli.lockfile.is_some() {
+        return Err(single("--lockfile"));
+    }
+
+    let output = match &cli.output {
+        Some(path) => {
+            let template = path.to_string_lossy().into_owned();
+            if !template.contains("{minecraft}") {
+                return Err(invalid_options(
+                    "--output needs a `{minecraft}` placeholder with several Minecraft versions",
+                ));
+            }
+            Some(template)
+        }
+        None => None,
+    };
+    let rendering = render_options(cli);
+    let template = read_template(cli)?;
+
+    let games = client.meta("game")?;
+
+    for (i, minecraft) in minecraft.iter().enumerate() {
+        let spinner = progress::start(
+            format!(
+                "Resolving {} versions for Minecraft {minecraft}…",
+                platform.name()
+            ),
+            !cli.quiet,
+        );
+        let options = resolve_options(platform, cli, Some(minecraft.clone()))?;
+        let versions = quilt_latest::resolve_with_games(client, &options, &games)?;
+        check_compatibility(cli, &versions)?;
+        warn_failures(&versions);
+        if cli.verify {
+            quilt_latest::verify(client, &versions)?;
+        }
+        drop(spinner);
+
+        if cli.print_versions {
+            if i > 0 {
+                println!();
+            }
+            print_versions(&versions);
+            continue;
+        }
+
+        let rendered = render(cli.format, template.as_deref(), &versions, &rendering)?;
+        let comment = match cli.format {
+            Format::Kotlin | Format::BuildGradle | Format::Settings => "//",
+            _ => "#",
+        };
+        match &output {
+            Some(template) => {
+                let path = PathBuf::from(template.replace("{minecraft}", &versions.minecraft));
+                if cli.dry_run {
+                    eprintln!("{comment} {}\n{rendered}", path.display());
+                    continue;
+                }
+                write_output(&path, &rendered, cli.append)?;
+            }
+            None if cli.dry_run => {
+                eprintln!("{comment} Minecraft {}\n{rendered}", versions.minecraft);
+            }
+            // JSON objects already name their Minecraft version, one per line.
+            None if cli.format == Format::Json && template.is_none() => println!("{rendered}"),
+            None => {
+                if i > 0 {
+                    println!();
+                }
+                println!("{comment} Minecraft {}\n{rendered}", versions.minecraft);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes a change to a catalog entry as `key: old -> new`.
+fn describe(change: &CatalogChange) -> String {
+    let old = change.old.as_deref().unwrap_or("(missing)");
+    format!("{}: {old} -> {}", change.key, change.new)
+}
+
+/// Sums up the lookups `--best-effort` left out.
+fn warn_failures(versions: &Versions) {
+    if !versions.failures.is_empty() {
+        log::warn!(
+            "Left out after failed lookups:\n  - {}",
+            versions.failures.join("\n  - ")
+        );
+    }
+}
+
+/// Warns about resolved versions built for a different Minecraft version, or
+/// fails with `--strict`.
+fn check_compatibility(cli: &Cli, versions: &Versions) -> Result<()> {
+    let problems = quilt_latest::incompatibilities(versions);
+    if cli.strict && !problems.is_empty() {
+        return Err(Error::Incompatible { problems }.into());
+    }
+
+    for problem in problems {
+        log::warn!("{problem}");
+    }
+    Ok(())
+}
+
+/// Stable releases a targeted Minecraft version may be behind the latest one
+/// before it is warned about.
+const OUTDATED_RELEASES: usize = 10;
+
+fn resolve_options(
+    platform: Platform,
+    cli: &Cli,
+    minecraft: Option<String>,
+) -> Result<ResolveOptions> {
+    let mappings = match (cli.mappings, platform) {
+        (None, _)
+        | (Some(Mappings::Quilt), Platform::Quilt)
+        | (Some(Mappings::Yarn), Platform::Fabric) => MappingsSource::Platform,
+        (Some(Mappings::Parchment), _) => MappingsSource::Parchment,
+        (Some(Mappings::Quilt), Platform::Fabric) => {
+            return Err(invalid_options("Quilt Mappings require `--loader quilt`"))
+        }
+        (Some(Mappings::Yarn), Platform::Quilt) => {
+            return Err(invalid_options("Yarn requires `--loader fabric`"))
+        }
+    };
+
+    Ok(ResolveOptions {
+        platform,
+        mappings,
+        minecraft,
+        minecraft_range: cli.minecraft_range.clone(),
+        snapshot: cli.snapshot || cli.channel >= Channel::Snapshot,
+        outdated_releases: (!cli.quiet).then_some(OUTDATED_RELEASES),
+        prerelease_loader: cli.allow_prerelease || cli.channel >= Channel::Beta,
+        unstable_mappings: cli.include_unstable_mappings || cli.channel >= Channel::Beta,
+        qfapi_fallback: cli.qfapi_fallback,
+        fabric_api_fallback: cli.fabric_api_fallback,
+        require_qfapi: cli.fail_if_qfapi_missing,
+        skip_qfapi: cli.no_qfapi,
+        skip_qsl: cli.no_qsl,
+        kotlin: cli.kotlin,
+        hashed: cli.hashed,
+        best_effort: cli.best_effort,
+        loader_version: cli.loader_version.clone(),
+        maven_repo: match (cli.maven_repo, cli.channel) {
+            (Some(repo), _) => repo.into(),
+            (None, Channel::Snapshot) => MavenRepo::Both,
+            (None, _) => MavenRepo::Release,
+        },
+        loom_snapshot: cli.loom_snapshot,
+        loom_version: cli.loom_version.clone(),
+        loom_major: cli.loom_major.clone(),
+        mappings_version: cli.mappings_version.clone(),
+        mappings_build: cli.mappings_build,
+        qfapi_version: cli.qfapi_version.clone(),
+        components: components(cli),
+    })
+}
+
+fn render_options(cli: &Cli) -> RenderOptions {
+    RenderOptions {
+        naming: CatalogNaming {
+            aliases: cli.alias.iter().cloned().collect(),
+            loom_id: cli.loom_plugin_id.clone(),
+            minecraft_module: cli.minecraft_module.clone(),
+        },
+        loom_range: cli.loom_range,
+        pretty_json: cli.json_pretty,
+        components: components(cli),
+    }
+}
+
+/// The components given with `--components`, or all of them without.
+fn components(cli: &Cli) -> Components {
+    if cli.components.is_empty() {
+        return Components::default();
+    }
+    let given = |component| cli.components.contains(&component);
+    Components {
+        minecraft: given(Emitted::Minecraft),
+        loader: given(Emitted::Loader),
+        mappings: given(Emitted::Mappings),
+        loom: given(Emitted::Loom),
+        qfapi: given(Emitted::Qfapi),
+        qsl: given(Emitted::Qsl),
+    }
+}
+
+fn read_template(cli: &Cli) -> Result<Option<String>> {
+    let Some(path) = &cli.template else {
+        return Ok(None);
+    };
+    let template =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(Some(template))
+}
+
+/// Renders the versions with `template` if given, or else in `format`.
+fn render(
+    format: Format,
+    template: Option<&str>,
+    versions: &Versions,
+    options: &RenderOptions,
+) -> Result<String> {
+    if let Some(template) = template {
+        // The newline is added back on output, like for the formats.
+        let mut rendered = quilt_latest::format_template(template, versions)?;
+        if rendered.ends_with('\n') {
+            rendered.pop();
+        }
+        return Ok(rendered);
+    }
+
+    let rendered = quilt_latest::render(versions, format.into(), options);
+    if format == Format::Catalog {
+        // Guards against writing a catalog Gradle would reject cryptically.
+        rendered
+            .parse::<toml_edit::DocumentMut>()
+            .context("the generated catalog is not valid TOML; check the aliases")?;
+    }
+    Ok(rendered)
+}
+
+/// Prints the resolved versions as an aligned table, for a human to read.
+fn print_versions(versions: &Versions) {
+    let platform = versions.platform;
+    let mappings = match versions.mappings_source {
+        MappingsSource::Platform => platform.mappings_name(),
+        MappingsSource::Parchment => "Parchment",
     };
-    
-    format!(
-r#"[versions]
-minecraft = "{minecraft}"
-quilt_loader = "{loader}"
-quilt_mappings = "{mappings}"
-
-{qfapi_version}
-
-[libraries]
-minecraft = {{ module = "com.mojang:minecraft", version.ref = "minecraft" }}
-quilt_loader = {{ module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }}
-quilt_mappings = {{ module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }}
-        
-{qfapi_lib_comment}quilted_fabric_api = {{ module = "org.quiltmc.quilted-fabric-api:quilted-fabric-api", version.ref = "quilted_fabric_api" }}
-
-[plugins]
-quilt_loom = {{ id = "org.quiltmc.loom", version = "{loom}" }}"#
-    )
+    let mut rows = vec![
+        ("Minecraft".to_string(), versions.minecraft.as_str()),
+        (format!("{} Loader", platform.name()), &versions.loader),
+        (mappings.to_string(), &versions.mappings),
+        (format!("{} Loom", platform.name()), &versions.loom),
+    ];
+    rows.retain(|(_, version)| !version.is_empty());
+    for library in versions.catalog_libraries() {
+        if !library.lookup.is_skipped() {
+            let version = library.lookup.found().unwrap_or("not found");
+            rows.push((library.name.to_string(), version));
+        }
+    }
+
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, version) in rows {
+        println!("{name:<width$}  {version}");
+    }
+}
+
+/// Appends step outputs to the file named by `$GITHUB_OUTPUT`, or prints them
+/// outside of GitHub Actions.
+fn write_github_output(outputs: &str) -> Result<()> {
+    let Some(path) = std::env::var_os("GITHUB_OUTPUT") else {
+        log::warn!("GITHUB_OUTPUT is not set; printing the outputs instead");
+        println!("{outputs}");
+        return Ok(());
+    };
+
+    let path = PathBuf::from(path);
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{outputs}"))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn parse_release_line(value: &str) -> Result<String, String> {
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    match value.split_once('.') {
+        Some((major, minor)) if is_number(major) && is_number(minor) => Ok(value.to_string()),
+        _ => Err("expected MAJOR.MINOR, e.g. 1.6".to_string()),
+    }
+}
+
+fn parse_module(value: &str) -> Result<String, String> {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+    };
+    match value.split_once(':') {
+        Some((group, artifact)) if valid(group) && valid(artifact) => Ok(value.to_string()),
+        _ => Err("expected GROUP:ARTIFACT".to_string()),
+    }
+}
+
+fn parse_alias(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((_, new))
+            if !new
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_-".contains(c)) =>
+        {
+            Err(format!(
+                "{new} is not a valid alias; use letters, digits, `_` and `-`"
+            ))
+        }
+        Some((default, new)) if !default.is_empty() && !new.is_empty() => {
+            Ok((default.to_string(), new.to_string()))
+        }
+        _ => Err("expected DEFAULT=NEW".to_string()),
+    }
+}
+
+/// Reads the `minecraft_version` property of a Java properties file.
+fn read_minecraft_property(path: &Path) -> Result<String> {
+    const KEY: &str = "minecraft_version";
+
+    let properties =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    properties
+        .lines()
+        .map(str::trim_start)
+        .filter(|line| !line.starts_with(['#', '!']))
+        .find_map(|line| {
+            let (key, value) = line.split_once(['=', ':'])?;
+            (key.trim_end() == KEY).then(|| value.trim().to_string())
+        })
+        .filter(|version| !version.is_empty())
+        .with_context(|| format!("{} has no `{KEY}` property", path.display()))
+}
+
+fn invalid_options(message: &str) -> anyhow::Error {
+    Error::InvalidOptions(message.to_string()).into()
+}
+
+/// Writes rendered output to `path`, or appends it after a newline if
+/// `append` is set.
+fn write_output(path: &Path, rendered: &str, append: bool) -> Result<()> {
+    let result = if append {
+        append_to(path, &format!("{rendered}\n"))
+    } else {
+        write_atomic(path, &format!("{rendered}\n"))
+    };
+    result.with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Appends `contents` to the file at `path`, separated from what's already
+/// there by a newline. The file is created if it doesn't exist.
+fn append_to(path: &Path, contents: &str) -> Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if file.metadata()?.len() > 0 {
+        file.write_all(b"\n")?;
+    }
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into
+/// place, so an interrupted write never leaves a partial file behind.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path.file_name().with_context(|| "not a file path")?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    Ok(result?)
 }
@@ -0,0 +1,286 @@
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::{CatalogNaming, Error, Result, Versions};
+
+/// A managed catalog entry that differs from the resolved version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogChange {
+    /// Dotted path of the entry, e.g. `versions.quilt_loader`.
+    pub key: String,
+    /// The current value, or `None` if the entry is missing.
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// Updates the entries managed by this tool in an existing version catalog,
+/// leaving every other entry, comment and the key ordering untouched.
+///
+/// Managed entries missing from the catalog are inserted into their table.
+pub fn merge_gradle_catalog(
+    existing: &str,
+    versions: &Versions,
+    naming: &CatalogNaming,
+) -> Result<String> {
+    let Versions { loom, platform, .. } = versions;
+    let loader_library = platform.loader_library();
+    let (mappings_alias, mappings_module) = versions.mappings_library();
+    let loom_plugin = platform.loom_plugin();
+
+    let mut doc: DocumentMut = existing.parse()?;
+
+    let table = table_mut(&mut doc, "versions")?;
+    for (alias, version) in managed_versions(versions, naming) {
+        set_string(table, alias, version);
+    }
+
+    let libraries = table_mut(&mut doc, "libraries")?;
+    insert_library(
+        libraries,
+        naming.alias("minecraft"),
+        naming.minecraft_module(),
+    );
+    insert_library(
+        libraries,
+        naming.alias(loader_library.alias),
+        loader_library.module,
+    );
+    insert_library(libraries, naming.alias(mappings_alias), &mappings_module);
+    for library in versions.catalog_libraries() {
+        if library.lookup.found().is_some() {
+            insert_library(libraries, naming.alias(library.alias), library.module);
+        }
+    }
+
+    let plugins = table_mut(&mut doc, "plugins")?;
+    let loom_alias = naming.alias(loom_plugin.alias);
+    let loom_id = naming.loom_id(*platform);
+    // Loom left out after its lookup failed keeps the version it had.
+    if !loom.is_empty() {
+        if let Some(version_ref) = set_plugin(plugins, loom_alias, loom_id, loom)? {
+            set_string(table_mut(&mut doc, "versions")?, &version_ref, loom);
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Compares the entries managed by this tool in an existing version catalog
+/// with the resolved versions, without modifying the catalog.
+///
+/// Returns no changes if the catalog is up to date.
+pub fn diff_gradle_catalog(
+    existing: &str,
+    versions: &Versions,
+    naming: &CatalogNaming,
+) -> Result<Vec<CatalogChange>> {
+    let doc: DocumentMut = existing.parse()?;
+    let version_entry = |alias: &str| {
+        doc.get("versions")
+            .and_then(|table| table.get(alias))
+            .and_then(Item::as_str)
+    };
+
+    let mut changes = Vec::new();
+    let mut compare = |key: String, old: Option<&str>, new: &str| {
+        if old != Some(new) {
+            changes.push(CatalogChange {
+                key,
+                old: old.map(String::from),
+                new: new.to_string(),
+            });
+        }
+    };
+
+    for (alias, version) in managed_versions(versions, naming) {
+        compare(format!("versions.{alias}"), version_entry(alias), version);
+    }
+
+    let alias = naming.alias(versions.platform.loom_plugin().alias);
+    let plugin = doc.get("plugins").and_then(|table| table.get(alias));
+    match plugin {
+        _ if versions.loom.is_empty() => {}
+        // Shorthand notation: `alias = "id:version"`
+        Some(Item::Value(Value::String(shorthand))) => compare(
+            format!("plugins.{alias}"),
+            shorthand
+                .value()
+                .rsplit_once(':')
+                .map(|(_, version)| version),
+            &versions.loom,
+        ),
+        Some(plugin) => match plugin.get("version") {
+            Some(version) if version.is_table_like() => {
+                let version_ref = version
+                    .get("ref")
+                    .and_then(Item::as_str)
+                    .unwrap_or_default();
+                compare(
+                    format!("versions.{version_ref}"),
+                    version_entry(version_ref),
+                    &versions.loom,
+                );
+            }
+            version => compare(
+                format!("plugins.{alias}.version"),
+                version.and_then(Item::as_str),
+                &versions.loom,
+            ),
+        },
+        None => compare(format!("plugins.{alias}"), None, &versions.loom),
+    }
+
+    Ok(changes)
+}
+
+/// The `[versions]` entries managed by this tool, except for the loom plugin's.
+fn managed_versions<'a>(
+    versions: &'a Versions,
+    naming: &'a CatalogNaming,
+) -> Vec<(&'a str, &'a str)> {
+    let mut managed = vec![
+        (naming.alias("minecraft"), versions.minecraft.as_str()),
+        (
+            naming.alias(versions.platform.loader_library().alias),
+            versions.loader.as_str(),
+        ),
+        (
+            naming.alias(versions.mappings_library().0),
+            versions.mappings.as_str(),
+        ),
+    ];
+    for library in versions.catalog_libraries() {
+        if let Some(version) = library.lookup.found() {
+            managed.push((naming.alias(library.alias), version));
+        }
+    }
+    managed
+}
+
+fn table_mut<'a>(doc: &'a mut DocumentMut, name: &str) -> Result<&'a mut dyn TableLike> {
+    doc.entry(name)
+        .or_insert(Item::Table(Table::new()))
+        .as_table_like_mut()
+        .ok_or_else(|| Error::InvalidCatalog(format!("`{name}` is not a table")))
+}
+
+/// Sets `key` to `new`, keeping the surrounding whitespace and comments of an
+/// existing value.
+fn set_string(table: &mut dyn TableLike, key: &str, new: &str) {
+    match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(value) => replace_value(value, new),
+        None => {
+            table.insert(key, toml_edit::value(new));
+        }
+    }
+}
+
+fn replace_value(value: &mut Value, new: &str) {
+    let decor = value.decor().clone();
+    *value = Value::from(new);
+    *value.decor_mut() = decor;
+}
+
+fn insert_library(table: &mut dyn TableLike, alias: &str, module: &str) {
+    if table.contains_key(alias) {
+        return;
+    }
+
+    let library: Value = format!(r#"{{ module = "{module}", version.ref = "{alias}" }}"#)
+        .parse()
+        .expect("library entry is valid TOML");
+    table.insert(alias, Item::Value(library));
+}
+
+/// Updates the version of a plugin entry, inserting the entry if it is missing.
+///
+/// If the plugin refers to a `[versions]` entry through `version.ref`, the name
+/// of that entry is returned so the caller can update it instead.
+fn set_plugin(
+    table: &mut dyn TableLike,
+    alias: &str,
+    id: &str,
+    version: &str,
+) -> Result<Option<String>> {
+    let Some(plugin) = table.get_mut(alias) else {
+        let plugin: Value = format!(r#"{{ id = "{id}", version = "{version}" }}"#)
+            .parse()
+            .expect("plugin entry is valid TOML");
+        table.insert(alias, Item::Value(plugin));
+        return Ok(None);
+    };
+
+    // Shorthand notation: `alias = "id:version"`
+    if let Some(value) = plugin.as_value_mut().filter(|v| v.is_str()) {
+        replace_value(value, &format!("{id}:{version}"));
+        return Ok(None);
+    }
+
+    let plugin = plugin.as_table_like_mut().ok_or_else(|| {
+        Error::InvalidCatalog(format!("plugin `{alias}` is neither a string nor a table"))
+    })?;
+
+    if let Some(version_ref) = plugin
+        .get("version")
+        .and_then(Item::as_table_like)
+        .and_then(|v| v.get("ref"))
+        .and_then(Item::as_str)
+    {
+        return Ok(Some(version_ref.to_string()));
+    }
+
+    set_string(plugin, "version", version);
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Lookup, MappingsSource, Platform, Repositories};
+
+    #[test]
+    fn merge_keeps_comments() {
+        let versions = Versions {
+            platform: Platform::Quilt,
+            minecraft: "1.20.1".to_string(),
+            loom: "1.7.4".to_string(),
+            loader: "0.26.4".to_string(),
+            mappings: "1.20.1+build.23".to_string(),
+            mappings_source: MappingsSource::Platform,
+            qfapi: Lookup::Skipped,
+            fabric_api: false,
+            qsl: Lookup::Skipped,
+            qkl: Lookup::Skipped,
+            hashed: Lookup::Skipped,
+            repositories: Repositories::default(),
+            failures: Vec::new(),
+        };
+        let existing = r#"# Shared by every subproject.
+[versions]
+quilt_loader = "0.19.0" # bump carefully
+minecraft = "1.19.4"
+
+# Not managed by quilt-latest.
+jetbrains_annotations = "24.0.1"
+quilt_mappings = "1.19.4+build.5"
+
+[libraries]
+minecraft = { module = "com.mojang:minecraft", version.ref = "minecraft" }
+quilt_loader = { module = "org.quiltmc:quilt-loader", version.ref = "quilt_loader" }
+quilt_mappings = { module = "org.quiltmc:quilt-mappings", version.ref = "quilt_mappings" }
+
+[plugins]
+# Keep in sync with settings.gradle.
+quilt_loom = { id = "org.quiltmc.loom", version = "1.1.0" } # loom
+"#;
+
+        let merged = merge_gradle_catalog(existing, &versions, &CatalogNaming::default()).unwrap();
+        assert_eq!(
+            merged,
+            existing
+                .replace(r#""0.19.0""#, r#""0.26.4""#)
+                .replace(r#""1.19.4""#, r#""1.20.1""#)
+                .replace("1.19.4+build.5", "1.20.1+build.23")
+                .replace(r#""1.1.0""#, r#""1.7.4""#)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::{MAVEN_URL, META_URL, SNAPSHOT_URL};
+
+/// Default base URL of the Fabric meta API.
+pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2/versions";
+/// Default base URL of the Fabric maven repository.
+pub const FABRIC_MAVEN_URL: &str = "https://maven.fabricmc.net";
+
+/// The mod loader a project is built for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    #[default]
+    Quilt,
+    Fabric,
+}
+
+/// A library entry in the version catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Library {
+    /// Catalog alias, also used as its version ref.
+    pub alias: &'static str,
+    pub module: &'static str,
+}
+
+/// A plugin entry in the version catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Plugin {
+    pub alias: &'static str,
+    pub id: &'static str,
+}
+
+impl Platform {
+    pub fn is_quilt(&self) -> bool {
+        *self == Platform::Quilt
+    }
+
+    /// Human-readable name of the platform.
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Quilt => "Quilt",
+            Platform::Fabric => "Fabric",
+        }
+    }
+
+    /// Default base URL of the meta API.
+    pub fn meta_url(self) -> &'static str {
+        match self {
+            Platform::Quilt => META_URL,
+            Platform::Fabric => FABRIC_META_URL,
+        }
+    }
+
+    /// Default base URL of the maven repository.
+    pub fn maven_url(self) -> &'static str {
+        match self {
+            Platform::Quilt => MAVEN_URL,
+            Platform::Fabric => FABRIC_MAVEN_URL,
+        }
+    }
+
+    /// Default base URL of the maven repository loom snapshots are published
+    /// to; Fabric publishes them alongside the releases.
+    pub fn snapshot_url(self) -> &'static str {
+        match self {
+            Platform::Quilt => SNAPSHOT_URL,
+            Platform::Fabric => FABRIC_MAVEN_URL,
+        }
+    }
+
+    /// Meta endpoint listing the mappings builds for a Minecraft version.
+    pub fn mappings_endpoint(self, minecraft: &str) -> String {
+        match self {
+            Platform::Quilt => format!("quilt-mappings/{minecraft}"),
+            Platform::Fabric => format!("yarn/{minecraft}"),
+        }
+    }
+
+    /// Maven package of the loom Gradle plugin.
+    pub fn loom_package(self) -> &'static str {
+        match self {
+            Platform::Quilt => "org.quiltmc.loom",
+            Platform::Fabric => "net.fabricmc.fabric-loom",
+        }
+    }
+
+    /// Maven package of the platform's API library.
+    pub fn api_package(self) -> &'static str {
+        match self {
+            Platform::Quilt => "org.quiltmc.quilted-fabric-api.quilted-fabric-api",
+            Platform::Fabric => "net.fabricmc.fabric-api.fabric-api",
+        }
+    }
+
+    pub fn loader_library(self) -> Library {
+        match self {
+            Platform::Quilt => Library {
+                alias: "quilt_loader",
+                module: "org.quiltmc:quilt-loader",
+            },
+            Platform::Fabric => Library {
+                alias: "fabric_loader",
+                module: "net.fabricmc:fabric-loader",
+            },
+        }
+    }
+
+    pub fn mappings_library(self) -> Library {
+        match self {
+            Platform::Quilt => Library {
+                alias: "quilt_mappings",
+                module: "org.quiltmc:quilt-mappings",
+            },
+            Platform::Fabric => Library {
+                alias: "yarn_mappings",
+                module: "net.fabricmc:yarn",
+            },
+        }
+    }
+
+    /// Human-readable name of the platform's mappings.
+    pub fn mappings_name(self) -> &'static str {
+        match self {
+            Platform::Quilt => "Quilt Mappings",
+            Platform::Fabric => "Yarn",
+        }
+    }
+
+    /// Classifier of the mappings artifact consumed by loom.
+    pub fn mappings_classifier(self) -> &'static str {
+        match self {
+            Platform::Quilt => "intermediary-v2",
+            Platform::Fabric => "v2",
+        }
+    }
+
+    pub fn api_library(self) -> Library {
+        match self {
+            Platform::Quilt => Library {
+                alias: "quilted_fabric_api",
+                module: "org.quiltmc.quilted-fabric-api:quilted-fabric-api",
+            },
+            Platform::Fabric => Library {
+                alias: "fabric_api",
+                module: "net.fabricmc.fabric-api:fabric-api",
+            },
+        }
+    }
+
+    /// Human-readable name of the platform's API library.
+    pub fn api_name(self) -> &'static str {
+        match self {
+            Platform::Quilt => "Quilted Fabric API",
+            Platform::Fabric => "Fabric API",
+        }
+    }
+
+    pub fn loom_plugin(self) -> Plugin {
+        match self {
+            Platform::Quilt => Plugin {
+                alias: "quilt_loom",
+                id: "org.quiltmc.loom",
+            },
+            Platform::Fabric => Plugin {
+                alias: "fabric_loom",
+                id: "fabric-loom",
+            },
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! A spinner on stderr while versions are fetched, so a slow network doesn't
+//! look like a hang.
+
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// The spinner being shown, if any.
+static SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Shows a spinner until dropped; it clears itself on drop.
+pub struct Spinner(Option<ProgressBar>);
+
+/// Starts a spinner labeled `message`, unless `enabled` is unset. It is hidden
+/// anyway if stderr isn't a terminal.
+pub fn start(message: String, enabled: bool) -> Spinner {
+    if !enabled {
+        return Spinner(None);
+    }
+
+    let bar = ProgressBar::new_spinner()
+        .with_style(ProgressStyle::with_template("{spinner} {msg}").expect("template is valid"))
+        .with_message(message);
+    bar.enable_steady_tick(Duration::from_millis(100));
+    *SPINNER.lock().unwrap() = Some(bar.clone());
+    Spinner(Some(bar))
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        if let Some(bar) = self.0.take() {
+            bar.finish_and_clear();
+            *SPINNER.lock().unwrap() = None;
+        }
+    }
+}
+
+/// Writes to stderr with the spinner cleared, for log records.
+pub struct Stderr;
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match SPINNER.lock().unwrap().as_ref() {
+            Some(bar) => bar.suspend(|| io::stderr().write(buf)),
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
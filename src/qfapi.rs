@@ -0,0 +1,213 @@
+use semver::{BuildMetadata, Version};
+
+use crate::Platform;
+
+/// Returns the Minecraft version an API version was built for, or `None` if
+/// its build metadata doesn't name one.
+///
+/// QFAPI versions carry the Fabric API version and the Minecraft version in
+/// their build metadata, e.g. `7.1.0+0.89.0-1.20.1` is built for `1.20.1`.
+/// Fabric API versions carry only the Minecraft version: `0.92.2+1.20.1`.
+pub fn minecraft_version(platform: Platform, version: &str) -> Option<&str> {
+    let (_, build) = version.split_once('+')?;
+    let minecraft = match platform {
+        Platform::Quilt => build.split_once('-')?.1,
+        Platform::Fabric => build,
+    };
+    // Minecraft versions start with a digit, snapshots like `24w33a` too.
+    let plausible = minecraft.starts_with(|c: char| c.is_ascii_digit())
+        && minecraft
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    Some(minecraft).filter(|_| plausible)
+}
+
+/// The API versions with the Minecraft version each was built for, skipping
+/// the ones whose build metadata doesn't name one rather than guessing.
+fn built_for(platform: Platform, versions: &[String]) -> impl Iterator<Item = (&String, &str)> {
+    versions.iter().filter_map(move |version| {
+        let minecraft = minecraft_version(platform, version);
+        if minecraft.is_none() {
+            log::debug!(
+                "skipping {} {version}, which doesn't tell the Minecraft version it is built for",
+                platform.api_name()
+            );
+        }
+        Some((version, minecraft?))
+    })
+}
+
+/// Finds the highest API version built for exactly `minecraft`.
+pub fn find<'a>(platform: Platform, versions: &'a [String], minecraft: &str) -> Option<&'a String> {
+    highest(
+        built_for(platform, versions)
+            .filter(|(_, built_for)| *built_for == minecraft)
+            .map(|(version, _)| version),
+    )
+}
+
+/// Finds the highest API version built for any Minecraft version sharing the
+/// major and minor version of `minecraft`, e.g. `1.20.1` for `1.20.2`.
+pub fn find_same_line<'a>(
+    platform: Platform,
+    versions: &'a [String],
+    minecraft: &str,
+) -> Option<&'a String> {
+    let line = release_line(minecraft)?;
+    highest(
+        built_for(platform, versions)
+            .filter(|(_, built_for)| release_line(built_for) == Some(line))
+            .map(|(version, _)| version),
+    )
+}
+
+/// Picks the highest of `versions` by their own version, ignoring the build
+/// metadata naming what they were built against; on a tie, or if none parse,
+/// the first one wins.
+fn highest<'a>(versions: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+    let key = |version: &str| {
+        Version::parse(version).ok().map(|mut version| {
+            version.build = BuildMetadata::EMPTY;
+            version
+        })
+    };
+    versions
+        .map(|version| (key(version), version))
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .map(|(_, version)| version)
+}
+
+/// Returns the `major.minor` part of a release version; `None` for snapshots.
+fn release_line(minecraft: &str) -> Option<&str> {
+    let mut parts = minecraft.split('.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(major) || !is_number(minor) {
+        return None;
+    }
+
+    Some(&minecraft[..major.len() + 1 + minor.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions() -> Vec<String> {
+        [
+            "11.0.0-alpha.3+0.102.0-1.21.1",
+            "7.6.0+0.100.7-1.20.6",
+            "7.1.0+0.89.0-1.20.1",
+            "7.0.6+0.85.0-1.20",
+            "5.0.0-beta.9+0.76.0-1.19.4",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    #[test]
+    fn extracts_minecraft_version() {
+        assert_eq!(
+            minecraft_version(Platform::Quilt, "7.1.0+0.89.0-1.20.1"),
+            Some("1.20.1")
+        );
+        assert_eq!(
+            minecraft_version(Platform::Quilt, "11.0.0-alpha.3+0.102.0-1.21.1"),
+            Some("1.21.1")
+        );
+        assert_eq!(
+            minecraft_version(Platform::Quilt, "7.0.0+0.83.0-1.20-pre1"),
+            Some("1.20-pre1")
+        );
+        assert_eq!(minecraft_version(Platform::Quilt, "7.0.0"), None);
+        assert_eq!(minecraft_version(Platform::Quilt, "7.0.0+0.83.0"), None);
+        assert_eq!(
+            minecraft_version(Platform::Fabric, "0.92.2+1.20.1"),
+            Some("1.20.1")
+        );
+        assert_eq!(minecraft_version(Platform::Fabric, "0.92.2"), None);
+        assert_eq!(minecraft_version(Platform::Quilt, "7.0.0+"), None);
+        assert_eq!(minecraft_version(Platform::Quilt, "7.0.0+0.83.0-"), None);
+        assert_eq!(
+            minecraft_version(Platform::Quilt, "7.0.0+0.83.0-local"),
+            None
+        );
+        assert_eq!(minecraft_version(Platform::Fabric, "0.92.2+build.1"), None);
+    }
+
+    #[test]
+    fn skips_versions_without_minecraft() {
+        let versions = [
+            "8.0.0",
+            "7.9.0+local",
+            "7.8.0+0.90.0-",
+            "7.1.0+0.89.0-1.20.1",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            find(Platform::Quilt, &versions, "1.20.1").unwrap(),
+            "7.1.0+0.89.0-1.20.1"
+        );
+        assert_eq!(
+            find_same_line(Platform::Quilt, &versions, "1.20.4").unwrap(),
+            "7.1.0+0.89.0-1.20.1"
+        );
+    }
+
+    #[test]
+    fn matches_minecraft_exactly() {
+        let versions = versions();
+        assert_eq!(
+            find(Platform::Quilt, &versions, "1.20").unwrap(),
+            "7.0.6+0.85.0-1.20"
+        );
+        assert_eq!(
+            find(Platform::Quilt, &versions, "1.20.1").unwrap(),
+            "7.1.0+0.89.0-1.20.1"
+        );
+        assert_eq!(find(Platform::Quilt, &versions, "1.20.2"), None);
+        assert_eq!(find(Platform::Quilt, &versions, "1.2"), None);
+    }
+
+    #[test]
+    fn falls_back_to_same_release_line() {
+        let versions = versions();
+        assert_eq!(
+            find_same_line(Platform::Quilt, &versions, "1.20.2").unwrap(),
+            "7.6.0+0.100.7-1.20.6"
+        );
+        assert_eq!(find_same_line(Platform::Quilt, &versions, "1.18.2"), None);
+        assert_eq!(find_same_line(Platform::Quilt, &versions, "24w33a"), None);
+    }
+
+    #[test]
+    fn picks_highest_of_several_builds() {
+        let versions = [
+            "7.4.0+0.90.0-1.20.1",
+            "7.10.0+0.92.0-1.20.1",
+            "7.10.0-beta.1+0.91.0-1.20.1",
+            "7.9.2+0.91.6-1.20.1",
+            "7.0.6+0.85.0-1.20",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            find(Platform::Quilt, &versions, "1.20.1").unwrap(),
+            "7.10.0+0.92.0-1.20.1"
+        );
+        assert_eq!(
+            find_same_line(Platform::Quilt, &versions, "1.20.2").unwrap(),
+            "7.10.0+0.92.0-1.20.1"
+        );
+
+        let versions = ["0.90.0+1.20.1", "0.92.2+1.20.1", "0.91.0+1.20.1"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            find(Platform::Fabric, &versions, "1.20.1").unwrap(),
+            "0.92.2+1.20.1"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Checks whether a newer release of quilt-latest itself is available.
+
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use semver::Version;
+use serde::Deserialize;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/TheCodeSquid/quilt-latest/releases/latest";
+const RELEASES_URL: &str = "https://github.com/TheCodeSquid/quilt-latest/releases";
+/// Kept short, as the check must never hold up a run for long.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// Looks up the latest release in the background, so it overlaps with the
+/// rest of the run.
+pub fn spawn(proxy: Option<String>) -> JoinHandle<Option<Version>> {
+    thread::spawn(move || match latest_release(proxy.as_deref()) {
+        Ok(version) => Some(version),
+        Err(err) => {
+            log::debug!("failed to check for a newer release: {err:#}");
+            None
+        }
+    })
+}
+
+/// Tells the user about a newer release found by [`spawn`]; any failure to
+/// find out is silent.
+pub fn report(check: JoinHandle<Option<Version>>) {
+    let Ok(Some(latest)) = check.join() else {
+        return;
+    };
+
+    let current = Version::parse(env!("CARGO_PKG_VERSION")).expect("package version is semver");
+    if latest > current {
+        log::info!("quilt-latest {latest} is available (this is {current}); see {RELEASES_URL}");
+    }
+}
+
+fn latest_release(proxy: Option<&str>) -> anyhow::Result<Version> {
+    let mut builder = ureq::AgentBuilder::new()
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .timeout(TIMEOUT)
+        .try_proxy_from_env(true);
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(ureq::Proxy::new(proxy)?);
+    }
+
+    let release: Release = builder
+        .build()
+        .get(LATEST_RELEASE_URL)
+        .call()?
+        .into_json()?;
+    Ok(Version::parse(release.tag_name.trim_start_matches('v'))?)
+}
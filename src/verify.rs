@@ -0,0 +1,54 @@
+use crate::{Client, Error, MappingsSource, Repository, Result, Versions};
+
+/// Checks that every resolved artifact is published to its maven repository,
+/// so the output doesn't fail later when Gradle resolves it.
+pub fn verify(client: &Client, versions: &Versions) -> Result<()> {
+    let platform = versions.platform;
+    let loom_module = match platform.loom_package().rsplit_once('.') {
+        Some((group, artifact)) => format!("{group}:{artifact}"),
+        None => platform.loom_package().to_string(),
+    };
+    let (_, mappings_module) = versions.mappings_library();
+    let mappings_repository = match versions.mappings_source {
+        MappingsSource::Platform => Repository::Maven,
+        MappingsSource::Parchment => Repository::Parchment,
+    };
+
+    let mut artifacts = vec![
+        (
+            Repository::Maven,
+            platform.loader_library().module.to_string(),
+            versions.loader.as_str(),
+        ),
+        (
+            mappings_repository,
+            mappings_module,
+            versions.mappings.as_str(),
+        ),
+    ];
+    artifacts.push((
+        versions.repositories.loom,
+        loom_module,
+        versions.loom.as_str(),
+    ));
+    for library in versions.catalog_libraries() {
+        if let Some(version) = library.lookup.found() {
+            artifacts.push((library.repository, library.module.to_string(), version));
+        }
+    }
+    // Snapshot POMs are published under timestamped names, so only releases
+    // can be looked up by version. Components left out have no version.
+    artifacts.retain(|(_, _, version)| !version.is_empty() && !version.ends_with("-SNAPSHOT"));
+
+    let mut missing = Vec::new();
+    for (repository, module, version) in &artifacts {
+        if !client.artifact_exists(*repository, module, version)? {
+            missing.push(format!("{module}:{version}"));
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(Error::NotPublished { artifacts: missing });
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Runs the binary against a local server serving a canned meta response.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::thread;
+
+const GAME: &str = r#"[
+    {"version": "1.21.1", "stable": true},
+    {"version": "1.21", "stable": true}
+]"#;
+
+/// Serves `GAME` on a random local port, returning its base URL.
+fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = String::new();
+            BufReader::new(&stream).read_line(&mut request).unwrap();
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+            let response = if path == "/meta/game" {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{GAME}",
+                    GAME.len()
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    base
+}
+
+/// Runs the binary with `args`, caching in `cache` and reading no config.
+fn run(cache: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_quilt-latest"))
+        .args(args)
+        .env("XDG_CACHE_HOME", cache)
+        .env("XDG_CONFIG_HOME", cache)
+        .env_remove("QUILT_META_URL")
+        .env_remove("QUILT_MAVEN_URL")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn lists_offline_after_the_subcommand() {
+    let cache = std::env::temp_dir().join(format!("quilt-latest-cli-{}", std::process::id()));
+    let meta = format!("{}/meta", serve());
+
+    let online = run(
+        &cache,
+        &["--meta-url", &meta, "--retries", "0", "list", "minecraft"],
+    );
+    assert!(online.status.success(), "{online:?}");
+
+    let offline = run(
+        &cache,
+        &["list", "minecraft", "--meta-url", &meta, "--offline"],
+    );
+    let _ = std::fs::remove_dir_all(&cache);
+    assert!(offline.status.success(), "{offline:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&offline.stdout),
+        "1.21.1 (stable)\n1.21 (stable)\n"
+    );
+}
+
+#[test]
+fn rejects_generation_options_with_a_subcommand() {
+    let cache =
+        std::env::temp_dir().join(format!("quilt-latest-cli-{}-format", std::process::id()));
+
+    let output = run(&cache, &["--format", "json", "list", "minecraft"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("the subcommand 'list' cannot be used with '--format <FORMAT>'"));
+}
+
+#[test]
+fn rejects_an_empty_minecraft_list() {
+    let cache = std::env::temp_dir().join(format!("quilt-latest-cli-{}-list", std::process::id()));
+    let list = cache.join("minecraft.txt");
+    std::fs::create_dir_all(&cache).unwrap();
+    std::fs::write(&list, "# Nothing yet.\n\n").unwrap();
+
+    let empty_path = run(&cache, &["--minecraft-list", ""]);
+    let empty_list = run(&cache, &["--minecraft-list", list.to_str().unwrap()]);
+    let _ = std::fs::remove_dir_all(&cache);
+
+    assert_eq!(empty_path.status.code(), Some(4), "{empty_path:?}");
+    assert_eq!(empty_list.status.code(), Some(4), "{empty_list:?}");
+    assert!(String::from_utf8_lossy(&empty_list.stderr).contains("lists no Minecraft versions"));
+}
+
+#[test]
+fn lets_the_command_line_win_over_a_clashing_config() {
+    let cache =
+        std::env::temp_dir().join(format!("quilt-latest-cli-{}-config", std::process::id()));
+    let base = serve();
+    let config = cache.join("quilt-latest.toml");
+    std::fs::create_dir_all(&cache).unwrap();
+    std::fs::write(
+        &config,
+        "no_qfapi = true\n\n[alias]\nquilted_fabric_api = \"qfapi\"\n",
+    )
+    .unwrap();
+
+    let output = run(
+        &cache,
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "--meta-url",
+            &format!("{base}/meta"),
+            "--maven-url",
+            &format!("{base}/maven"),
+            "--retries",
+            "0",
+            "--minecraft",
+            "1.21",
+            "--components",
+            "minecraft,qfapi",
+            "--qfapi-version",
+            "0.100.0",
+        ],
+    );
+    let _ = std::fs::remove_dir_all(&cache);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("qfapi = \"0.100.0\""));
+}
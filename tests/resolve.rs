@@ -0,0 +1,456 @@
+//! Resolves versions against a local server serving canned meta and maven
+//! responses.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use quilt_latest::{
+    CacheMode, Client, Components, Error, Lookup, MavenRepo, MetaEntry, Platform, Repository,
+    ResolveOptions,
+};
+
+const GAME: &str = r#"[
+    {"version": "24w33a", "stable": false},
+    {"version": "1.21.1", "stable": true},
+    {"version": "1.21", "stable": true},
+    {"version": "1.20.1", "stable": true}
+]"#;
+
+const LOADER: &str = r#"[
+    {"separator": ".", "build": 1, "maven": "org.quiltmc:quilt-loader:0.27.0-beta.1", "version": "0.27.0-beta.1"},
+    {"separator": ".", "build": 4, "maven": "org.quiltmc:quilt-loader:0.26.4", "version": "0.26.4"},
+    {"separator": ".", "build": 3, "maven": "org.quiltmc:quilt-loader:0.26.3", "version": "0.26.3"}
+]"#;
+
+const MAPPINGS_1_21_1: &str = r#"[
+    {"gameVersion": "1.21.1", "separator": "+build.", "build": 3, "version": "1.21.1+build.3"},
+    {"gameVersion": "1.21.1", "separator": "+build.", "build": 2, "version": "1.21.1+build.2"}
+]"#;
+
+const MAPPINGS_1_21: &str = r#"[
+    {"gameVersion": "1.21", "separator": "+build.", "build": 1, "version": "1.21+build.1"}
+]"#;
+
+const MAPPINGS_1_20_1: &str = r#"[
+    {"gameVersion": "1.20.1", "separator": "+build.", "build": 23, "version": "1.20.1+build.23"}
+]"#;
+
+const LOOM: &str = "1.6.12 1.7.4 1.7.3";
+
+const LOOM_SNAPSHOTS: &str = "1.8.0-alpha.2";
+
+const QFAPI: &str = "7.0.6+0.85.0-1.20 11.0.0-alpha.3+0.102.0-1.21.1 7.6.0+0.100.7-1.20.6";
+
+const QSL: &str = "6.1.2+1.20.1 10.0.0-alpha.1+1.21.1";
+
+const HASHED: &str = "1.20.1 1.21.1";
+
+/// Serves the fixtures on a random local port, returning its base URL.
+fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = String::new();
+            BufReader::new(&stream).read_line(&mut request).unwrap();
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+            let response = match respond(path) {
+                Some(body) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+                None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    base
+}
+
+fn respond(path: &str) -> Option<String> {
+    let body = match path {
+        "/meta/game" => GAME.to_string(),
+        "/meta/loader" => LOADER.to_string(),
+        "/meta/quilt-mappings/1.21.1" => MAPPINGS_1_21_1.to_string(),
+        "/meta/quilt-mappings/1.21" => MAPPINGS_1_21.to_string(),
+        "/meta/quilt-mappings/1.20.1" => MAPPINGS_1_20_1.to_string(),
+        "/maven/org/quiltmc/loom/maven-metadata.xml" => metadata(LOOM),
+        "/snapshot/org/quiltmc/loom/maven-metadata.xml" => metadata(LOOM_SNAPSHOTS),
+        "/snapshot/org/quiltmc/loom/1.8.0-alpha.2/loom-1.8.0-alpha.2.pom" => {
+            "<project/>".to_string()
+        }
+        "/maven/org/quiltmc/quilted-fabric-api/quilted-fabric-api/maven-metadata.xml" => {
+            metadata(QFAPI)
+        }
+        "/maven/org/quiltmc/qsl/maven-metadata.xml" => metadata(QSL),
+        "/maven/org/quiltmc/hashed/maven-metadata.xml" => metadata(HASHED),
+        _ => return None,
+    };
+    Some(body)
+}
+
+fn metadata(versions: &str) -> String {
+    let versions: String = versions
+        .split_whitespace()
+        .map(|version| format!("<version>{version}</version>"))
+        .collect();
+    format!("<metadata><versioning><versions>{versions}</versions></versioning></metadata>")
+}
+
+fn client() -> Client {
+    let base = serve();
+    Client::builder()
+        .retries(0)
+        .meta_url(format!("{base}/meta"))
+        .maven_url(format!("{base}/maven"))
+        .snapshot_url(format!("{base}/snapshot"))
+        .cache(CacheMode::Disabled)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn resolves_latest_stable() {
+    let versions = quilt_latest::resolve(&client(), &ResolveOptions::default()).unwrap();
+
+    assert_eq!(versions.minecraft, "1.21.1");
+    assert_eq!(versions.loader, "0.26.4");
+    assert_eq!(versions.mappings, "1.21.1+build.3");
+    assert_eq!(versions.loom, "1.7.4");
+    assert_eq!(
+        versions.qfapi,
+        Lookup::Found("11.0.0-alpha.3+0.102.0-1.21.1".to_string())
+    );
+    assert_eq!(
+        versions.qsl,
+        Lookup::Found("10.0.0-alpha.1+1.21.1".to_string())
+    );
+    assert_eq!(versions.qkl, Lookup::Skipped);
+}
+
+#[test]
+fn skips_prerelease_loader_unless_allowed() {
+    let client = client();
+
+    let versions = quilt_latest::resolve(&client, &ResolveOptions::default()).unwrap();
+    assert_eq!(versions.loader, "0.26.4");
+
+    let options = ResolveOptions {
+        prerelease_loader: true,
+        ..ResolveOptions::default()
+    };
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(versions.loader, "0.27.0-beta.1");
+}
+
+#[test]
+fn matches_qfapi_to_minecraft() {
+    let client = client();
+    let options = ResolveOptions {
+        minecraft: Some("1.20.1".to_string()),
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(versions.mappings, "1.20.1+build.23");
+    assert_eq!(versions.qfapi, Lookup::NotFound);
+    assert_eq!(versions.qsl, Lookup::Found("6.1.2+1.20.1".to_string()));
+
+    let options = ResolveOptions {
+        qfapi_fallback: true,
+        ..options
+    };
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(
+        versions.qfapi,
+        Lookup::Found("7.6.0+0.100.7-1.20.6".to_string())
+    );
+}
+
+#[test]
+fn fails_without_qfapi_if_required() {
+    let client = client();
+    let options = ResolveOptions {
+        minecraft: Some("1.20.1".to_string()),
+        require_qfapi: true,
+        ..ResolveOptions::default()
+    };
+
+    let err = quilt_latest::resolve(&client, &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no Quilted Fabric API compatible with Minecraft version 1.20.1"
+    );
+
+    let fallback = ResolveOptions {
+        qfapi_fallback: true,
+        ..options.clone()
+    };
+    assert!(quilt_latest::resolve(&client, &fallback).is_ok());
+
+    // Leaving the API out would pass without looking for it.
+    let left_out = ResolveOptions {
+        components: Components {
+            qfapi: false,
+            ..Components::default()
+        },
+        ..options
+    };
+    let err = quilt_latest::resolve(&client, &left_out).unwrap_err();
+    assert!(matches!(err, Error::InvalidOptions(_)), "{err}");
+}
+
+#[test]
+fn snapshot_without_mappings() {
+    let options = ResolveOptions {
+        snapshot: true,
+        ..ResolveOptions::default()
+    };
+
+    let err = quilt_latest::resolve(&client(), &options).unwrap_err();
+    assert!(
+        matches!(&err, Error::NoMappings { minecraft } if minecraft == "24w33a"),
+        "{err}"
+    );
+}
+
+#[test]
+fn resolves_only_given_components() {
+    // Without mappings, a snapshot that has none resolves.
+    let options = ResolveOptions {
+        snapshot: true,
+        components: Components {
+            mappings: false,
+            loom: false,
+            qfapi: false,
+            qsl: false,
+            ..Components::default()
+        },
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client(), &options).unwrap();
+    assert_eq!(versions.minecraft, "24w33a");
+    assert_eq!(versions.loader, "0.26.4");
+    assert_eq!(versions.mappings, "");
+    assert_eq!(versions.loom, "");
+    assert_eq!(versions.qfapi, Lookup::Skipped);
+    assert_eq!(versions.qsl, Lookup::Skipped);
+}
+
+#[test]
+fn accepts_latest_keywords() {
+    let client = client();
+    let options = ResolveOptions {
+        minecraft: Some("latest".to_string()),
+        snapshot: true,
+        ..ResolveOptions::default()
+    };
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(versions.minecraft, "1.21.1");
+
+    let options = ResolveOptions {
+        minecraft: Some("latest-snapshot".to_string()),
+        ..ResolveOptions::default()
+    };
+    let err = quilt_latest::resolve(&client, &options).unwrap_err();
+    assert!(
+        matches!(&err, Error::NoMappings { minecraft } if minecraft == "24w33a"),
+        "{err}"
+    );
+}
+
+#[test]
+fn selects_loom_line() {
+    let options = |line: &str| ResolveOptions {
+        loom_major: Some(line.to_string()),
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client(), &options("1.6")).unwrap();
+    assert_eq!(versions.loom, "1.6.12");
+
+    let err = quilt_latest::resolve(&client(), &options("1.5")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no loom 1.5 release; available lines: 1.7, 1.6"
+    );
+}
+
+#[test]
+fn leaves_out_failed_lookups_with_best_effort() {
+    // QKL isn't served, so looking it up fails.
+    let options = |best_effort| ResolveOptions {
+        kotlin: true,
+        best_effort,
+        ..ResolveOptions::default()
+    };
+
+    assert!(quilt_latest::resolve(&client(), &options(false)).is_err());
+
+    let versions = quilt_latest::resolve(&client(), &options(true)).unwrap();
+    assert_eq!(versions.qkl, Lookup::NotFound);
+    assert_eq!(
+        versions.qsl,
+        Lookup::Found("10.0.0-alpha.1+1.21.1".to_string())
+    );
+    assert_eq!(versions.failures.len(), 1);
+    assert!(
+        versions.failures[0].starts_with("QKL: "),
+        "{:?}",
+        versions.failures
+    );
+
+    // Nothing is published under this maven URL, so loom isn't found either.
+    let base = serve();
+    let client = Client::builder()
+        .retries(0)
+        .meta_url(format!("{base}/meta"))
+        .maven_url(format!("{base}/missing"))
+        .cache(CacheMode::Disabled)
+        .build()
+        .unwrap();
+    let options = ResolveOptions {
+        skip_qfapi: true,
+        skip_qsl: true,
+        ..options(true)
+    };
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(versions.loom, "");
+    assert_eq!(versions.loader, "0.26.4");
+    assert!(
+        versions.failures[0].starts_with("loom: "),
+        "{:?}",
+        versions.failures
+    );
+}
+
+#[test]
+fn resolves_hashed_mappings() {
+    let options = |minecraft: &str| ResolveOptions {
+        minecraft: Some(minecraft.to_string()),
+        hashed: true,
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client(), &options("1.21.1")).unwrap();
+    assert_eq!(versions.hashed, Lookup::Found("1.21.1".to_string()));
+
+    let versions = quilt_latest::resolve(&client(), &options("1.21")).unwrap();
+    assert_eq!(versions.hashed, Lookup::NotFound);
+}
+
+#[test]
+fn tells_unknown_minecraft_from_missing_mappings() {
+    let client = client();
+
+    let err = quilt_latest::list_mappings(&client, Platform::Quilt, "1.21.2").unwrap_err();
+    assert!(
+        matches!(&err, Error::UnknownMinecraft { version, .. } if version == "1.21.2"),
+        "{err}"
+    );
+
+    let err = quilt_latest::list_mappings(&client, Platform::Quilt, "24w33a").unwrap_err();
+    assert!(
+        matches!(&err, Error::NoMappings { minecraft } if minecraft == "24w33a"),
+        "{err}"
+    );
+}
+
+#[test]
+fn resolves_against_given_games() {
+    let games: Vec<MetaEntry> =
+        serde_json::from_str(r#"[{"version": "1.20.1", "stable": true}]"#).unwrap();
+
+    let versions =
+        quilt_latest::resolve_with_games(&client(), &ResolveOptions::default(), &games).unwrap();
+    assert_eq!(versions.minecraft, "1.20.1");
+    assert_eq!(versions.mappings, "1.20.1+build.23");
+}
+
+#[test]
+fn falls_back_to_newest_without_stability() {
+    let games: Vec<MetaEntry> =
+        serde_json::from_str(r#"[{"version": "1.21.1"}, {"version": "1.20.1"}]"#).unwrap();
+
+    let versions =
+        quilt_latest::resolve_with_games(&client(), &ResolveOptions::default(), &games).unwrap();
+    assert_eq!(versions.minecraft, "1.21.1");
+}
+
+#[test]
+fn suggests_closest_minecraft() {
+    let options = ResolveOptions {
+        minecraft: Some("1.20.11".to_string()),
+        ..ResolveOptions::default()
+    };
+
+    let err = quilt_latest::resolve(&client(), &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Minecraft version 1.20.11 not found; did you mean 1.20.1?"
+    );
+}
+
+#[test]
+fn selects_newest_in_range() {
+    let options = ResolveOptions {
+        minecraft_range: Some(">=1.20, <1.21.1".parse().unwrap()),
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client(), &options).unwrap();
+    assert_eq!(versions.minecraft, "1.21");
+}
+
+#[test]
+fn verifies_snapshots_in_the_snapshot_repository() {
+    let client = client();
+    let options = ResolveOptions {
+        maven_repo: MavenRepo::Both,
+        components: Components {
+            loader: false,
+            mappings: false,
+            qfapi: false,
+            qsl: false,
+            ..Components::default()
+        },
+        ..ResolveOptions::default()
+    };
+
+    let versions = quilt_latest::resolve(&client, &options).unwrap();
+    assert_eq!(versions.loom, "1.8.0-alpha.2");
+    assert_eq!(versions.repositories.loom, Repository::Snapshot);
+    quilt_latest::verify(&client, &versions).unwrap();
+}
+
+#[test]
+fn selects_minecraft_alone() {
+    let games: Vec<MetaEntry> = serde_json::from_str(GAME).unwrap();
+    let select = |minecraft: &str| {
+        let options = ResolveOptions {
+            minecraft: Some(minecraft.to_string()),
+            ..ResolveOptions::default()
+        };
+        quilt_latest::select_minecraft(&games, &options).unwrap()
+    };
+
+    assert_eq!(select("latest"), "1.21.1");
+    assert_eq!(select("latest-snapshot"), "24w33a");
+    assert_eq!(select("1.20"), "1.20.1");
+
+    let options = ResolveOptions {
+        minecraft_range: Some("<1.21.1".parse().unwrap()),
+        ..ResolveOptions::default()
+    };
+    assert_eq!(
+        quilt_latest::select_minecraft(&games, &options).unwrap(),
+        "1.21"
+    );
+}